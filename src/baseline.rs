@@ -0,0 +1,243 @@
+// Named baselines and regression detection against them
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use colored::*;
+
+use eonflux_benchmark::export::BenchmarkReport;
+use crate::history::{data_dir, file_stem};
+
+pub const DEFAULT_TOLERANCE_PCT: f64 = 5.0;
+
+fn baselines_dir() -> PathBuf {
+    data_dir().join("baselines")
+}
+
+/// Baselines are referred to by name; anything that looks like a path is used as-is,
+/// so a JSON export from another machine can serve as a baseline too.
+pub fn path_for(name: &str) -> PathBuf {
+    if name.ends_with(".json") || name.contains('/') || name.contains('\\') {
+        PathBuf::from(name)
+    } else {
+        baselines_dir().join(format!("{}.json", file_stem(name)))
+    }
+}
+
+pub fn save(name: &str, report: &BenchmarkReport) -> io::Result<PathBuf> {
+    if name.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "a baseline needs a name"));
+    }
+    let path = path_for(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    report.write_json(&path)?;
+    Ok(path)
+}
+
+pub fn load(name: &str) -> io::Result<BenchmarkReport> {
+    let path = path_for(name);
+    let contents = fs::read_to_string(&path).map_err(|e| {
+        io::Error::new(e.kind(), format!("could not read baseline '{}' ({}): {}", name, path.display(), e))
+    })?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+pub fn delete(name: &str) -> io::Result<()> {
+    fs::remove_file(path_for(name))
+}
+
+/// Saved baselines by name, sorted alphabetically
+pub fn list() -> io::Result<Vec<(String, BenchmarkReport)>> {
+    let entries = match fs::read_dir(baselines_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut baselines = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        // Skip files that are not reports instead of failing the whole listing
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if let Ok(report) = serde_json::from_str::<BenchmarkReport>(&contents) {
+            baselines.push((name.to_string(), report));
+        }
+    }
+    baselines.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(baselines)
+}
+
+pub fn print_list(baselines: &[(String, BenchmarkReport)]) {
+    if baselines.is_empty() {
+        println!("No baselines saved yet.");
+        return;
+    }
+    println!("{:<24} {:<19}  {:<20} Benchmarks", "Name", "Recorded", "Host");
+    for (name, report) in baselines {
+        println!(
+            "{:<24} {:<19}  {:<20} {}",
+            name,
+            report.started_at.format("%Y-%m-%d %H:%M:%S"),
+            report.machine.host_name,
+            report.benchmarks.join(", ")
+        );
+    }
+}
+
+pub struct MetricComparison {
+    pub label: &'static str,
+    pub unit: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    /// Relative change in percent, positive when the value went up
+    pub change_pct: f64,
+    pub regression: bool,
+    pub improvement: bool,
+}
+
+/// Compares every metric measured in both reports
+pub fn compare(baseline: &BenchmarkReport, current: &BenchmarkReport, tolerance_pct: f64) -> Vec<MetricComparison> {
+    let ran_in_both = |benchmark: &str| baseline.ran(benchmark) && current.ran(benchmark);
+
+    baseline
+        .results
+        .metrics()
+        .iter()
+        .zip(current.results.metrics().iter())
+        .filter(|(metric, _)| ran_in_both(metric.benchmark))
+        .map(|(old, new)| {
+            let change_pct = if old.value != 0.0 {
+                (new.value - old.value) / old.value * 100.0
+            } else {
+                0.0
+            };
+            // Normalize so that a positive change always means "better"
+            let gain = if old.higher_is_better { change_pct } else { -change_pct };
+            MetricComparison {
+                label: old.label,
+                unit: old.unit,
+                baseline: old.value,
+                current: new.value,
+                change_pct,
+                regression: gain < -tolerance_pct,
+                improvement: gain > tolerance_pct,
+            }
+        })
+        .collect()
+}
+
+pub fn print_comparison(name: &str, baseline: &BenchmarkReport, comparisons: &[MetricComparison], tolerance_pct: f64) {
+    println!(
+        "{} '{}' ({} on {}), tolerance ±{:.1}%",
+        "Comparison with baseline".bright_yellow(),
+        name,
+        baseline.started_at.format("%Y-%m-%d %H:%M:%S"),
+        baseline.machine.host_name,
+        tolerance_pct
+    );
+    if comparisons.is_empty() {
+        println!("No metrics in common with this baseline.");
+        return;
+    }
+
+    println!(
+        "{:<16} {:>14} {:>14} {:>9}  Status",
+        "Metric", "Baseline", "Current", "Change"
+    );
+    for c in comparisons {
+        let status = if c.regression {
+            "REGRESSION".bright_red()
+        } else if c.improvement {
+            "Improved".bright_green()
+        } else {
+            "OK".normal()
+        };
+        println!(
+            "{:<16} {:>14.2} {:>14.2} {:>8.1}%  {} ({})",
+            c.label, c.baseline, c.current, c.change_pct, status, c.unit
+        );
+    }
+
+    let regressions = comparisons.iter().filter(|c| c.regression).count();
+    if regressions > 0 {
+        println!("\n{} {} metric(s) regressed beyond the tolerance", "►".bright_red(), regressions);
+    } else {
+        println!("\n{} No regressions found", "►".bright_green());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_report as report;
+
+    fn compare_one(key: &str, label: &str, old: f64, new: f64, tolerance_pct: f64) -> MetricComparison {
+        let benchmark = if key.starts_with("cpu") { "cpu" } else { "memory" };
+        let comparisons = compare(&report(&[benchmark], &[(key, old)]), &report(&[benchmark], &[(key, new)]), tolerance_pct);
+        comparisons.into_iter().find(|c| c.label == label).unwrap()
+    }
+
+    #[test]
+    fn a_drop_is_a_regression_when_higher_is_better() {
+        let c = compare_one("cpu_score", "CPU Score", 1000.0, 900.0, 5.0);
+        assert_eq!(c.change_pct, -10.0);
+        assert!(c.regression && !c.improvement);
+
+        let c = compare_one("cpu_score", "CPU Score", 1000.0, 1100.0, 5.0);
+        assert!(c.improvement && !c.regression);
+    }
+
+    #[test]
+    fn a_rise_is_a_regression_when_lower_is_better() {
+        let c = compare_one("memory_latency", "Memory Latency", 100.0, 110.0, 5.0);
+        assert!(c.regression && !c.improvement);
+
+        let c = compare_one("memory_latency", "Memory Latency", 100.0, 90.0, 5.0);
+        assert!(c.improvement && !c.regression);
+    }
+
+    #[test]
+    fn a_change_of_exactly_the_tolerance_is_neither() {
+        let c = compare_one("cpu_score", "CPU Score", 100.0, 75.0, 25.0);
+        assert!(!c.regression && !c.improvement);
+        let c = compare_one("cpu_score", "CPU Score", 100.0, 74.0, 25.0);
+        assert!(c.regression);
+
+        let c = compare_one("memory_latency", "Memory Latency", 100.0, 125.0, 25.0);
+        assert!(!c.regression && !c.improvement);
+        let c = compare_one("memory_latency", "Memory Latency", 100.0, 126.0, 25.0);
+        assert!(c.regression);
+    }
+
+    #[test]
+    fn only_benchmarks_that_ran_in_both_are_compared() {
+        let baseline = report(&["cpu", "memory"], &[("cpu_score", 1000.0), ("memory_latency", 100.0)]);
+        let current = report(&["cpu"], &[("cpu_score", 1000.0)]);
+        let comparisons = compare(&baseline, &current, 5.0);
+        assert!(comparisons.iter().any(|c| c.label == "CPU Score"));
+        assert!(!comparisons.iter().any(|c| c.label == "Memory Latency"));
+
+        let comparisons = compare(&current, &baseline, 5.0);
+        assert!(!comparisons.iter().any(|c| c.label == "Memory Latency"));
+    }
+
+    #[test]
+    fn a_metric_missing_from_the_baseline_has_no_change() {
+        let baseline = report(&["cpu"], &[("cpu_score", 1000.0)]);
+        let current = report(&["cpu"], &[("cpu_score", 1000.0), ("cpu_multi_score", 4000.0)]);
+        let c = compare(&baseline, &current, 5.0).into_iter().find(|c| c.label == "CPU All-Core").unwrap();
+        assert_eq!(c.change_pct, 0.0);
+        assert!(!c.regression && !c.improvement);
+    }
+}
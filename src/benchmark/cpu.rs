@@ -0,0 +1,39 @@
+// CPU throughput on 1, 2, 4 … N threads
+
+use std::io;
+
+use sysinfo::SystemExt;
+
+use super::{Benchmark, Context};
+use crate::{cancel, cpu, BenchmarkResults};
+
+#[derive(Default)]
+pub struct CpuBenchmark;
+
+impl Benchmark for CpuBenchmark {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn title(&self) -> &'static str {
+        "CPU Benchmark"
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let profile = &ctx.settings.profile;
+        let max_threads = profile.cpu.max_threads.unwrap_or(ctx.sys.cpus().len());
+        let progress = ctx.progress;
+        progress.start(&format!("CPU stress test on up to {} threads", max_threads), 100);
+        let points = cpu::measure_scaling(max_threads, profile.cpu_duration(), |threads, fraction| {
+            progress.set_message(&format!("{} thread(s)", threads));
+            progress.set_position((fraction * 100.0) as u64);
+        });
+        cancel::check()?;
+        progress.set_position(100);
+
+        results.cpu_score = points.first().map_or(0.0, |p| p.ops_per_sec);
+        results.cpu_multi_score = points.last().map_or(0.0, |p| p.ops_per_sec);
+        results.cpu_scaling = points;
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+// Disk benchmarks: the standard sequential and 4 KB random tests, the block-size sweep,
+// queue-depth scaling, durable writes and file-system metadata operations
+
+use std::io;
+
+use sysinfo::SystemExt;
+
+use super::{Benchmark, Context};
+use crate::cleanup::TempPath;
+use crate::disk::{self, DiskTarget, SyncMethod};
+use crate::{latency, preflight, BenchmarkResults, DISK_CHUNK_SIZE, DISK_MIN_TEST_SIZE};
+
+/// The volume the settings point the test files at, with the disk list refreshed so that
+/// volumes mounted since startup are recognized
+fn resolve_target(ctx: &mut Context) -> io::Result<DiskTarget> {
+    ctx.sys.refresh_disks_list();
+    DiskTarget::resolve(ctx.sys, ctx.settings.disk_dir.as_deref())
+}
+
+/// Disk space for a test file of `wanted` bytes, scaled down as far as the test allows but
+/// never below one `block`
+fn file_size(ctx: &mut Context, target: &DiskTarget, test: &str, wanted: usize, block: usize) -> io::Result<usize> {
+    preflight::disk_space(ctx, target, test, wanted, DISK_MIN_TEST_SIZE.min(wanted), block)
+}
+
+/// What `setup` prepared, or an error when `run` is called without it
+fn prepared<T>(value: &Option<T>) -> io::Result<&T> {
+    value.as_ref().ok_or_else(|| io::Error::other("the benchmark was run before its setup"))
+}
+
+/// Sequential 1 MB reads and writes and small-block random read, write and mixed tests on one
+/// test file, and the sequential tests again with direct I/O when it is enabled
+#[derive(Default)]
+pub struct DiskBenchmark {
+    target: Option<DiskTarget>,
+    size: usize,
+    test_file: Option<TempPath>,
+}
+
+impl Benchmark for DiskBenchmark {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    fn title(&self) -> &'static str {
+        "Disk Benchmark"
+    }
+
+    fn benchmarks(&self, settings: &crate::BenchmarkSettings) -> Vec<&'static str> {
+        if settings.direct_io {
+            vec!["disk", "disk-direct"]
+        } else {
+            vec!["disk"]
+        }
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        let target = resolve_target(ctx)?;
+        let profile = &ctx.settings.profile;
+        let block = DISK_CHUNK_SIZE.max(profile.disk_random_block_size());
+        self.size = file_size(ctx, &target, "Disk test", profile.disk_size(), block)?;
+        self.test_file = Some(TempPath::new(target.test_file()));
+        self.target = Some(target);
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        let test_file = prepared(&self.test_file)?;
+        let size = self.size;
+        let chunks = (size / DISK_CHUNK_SIZE) as u64;
+        let mut file = disk::create_test_file(test_file, false)?;
+
+        progress.start("Sequential write", chunks);
+        let write = disk::sequential_write(&mut file, size, DISK_CHUNK_SIZE, |done| progress.set_position(done as u64))?;
+        progress.start("Sequential read", chunks);
+        let read = disk::sequential_read(&mut file, size, DISK_CHUNK_SIZE, |done| progress.set_position(done as u64))?;
+
+        let profile = &ctx.settings.profile;
+        let (block_size, ops) = (profile.disk_random_block_size(), profile.disk.random_ops);
+        let random_tests = [100, 0, ctx.settings.mixed_read_pct]
+            .into_iter()
+            .map(|read_pct| {
+                progress.start(&format!("{}K {}", block_size / 1024, disk::random_test_name(read_pct)), ops as u64);
+                disk::run_random(&mut file, size, block_size, ops, read_pct, |done| progress.set_position(done as u64))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        drop(file);
+        std::fs::remove_file(test_file)?;
+
+        // The same sequential tests with the page cache bypassed
+        if ctx.settings.direct_io {
+            progress.start("Sequential write and read with direct I/O", 2 * chunks);
+            (results.disk_direct_write_speed, results.disk_direct_read_speed) =
+                disk::sequential_uncached(test_file, size, DISK_CHUNK_SIZE, |done| progress.set_position(done as u64))?;
+        }
+
+        results.disk_write_speed = write.mb_per_sec;
+        results.disk_read_speed = read.mb_per_sec;
+        results.disk_sequential_write_latency = write.latency;
+        results.disk_sequential_read_latency = read.latency;
+        let p99 = |s: &Option<latency::LatencySummary>| s.as_ref().map_or(0.0, |s| s.p99_us);
+        if let [read, write, mixed] = &random_tests[..] {
+            results.disk_random_read_iops = read.throughput.iops;
+            results.disk_random_write_iops = write.throughput.iops;
+            results.disk_mixed_iops = mixed.throughput.iops;
+            results.disk_read_p99 = p99(&read.read_latency);
+            results.disk_write_p99 = p99(&write.write_latency);
+        }
+        results.disk_random = random_tests;
+        results.disk_target = self.target.clone();
+        Ok(())
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        self.test_file = None;
+        Ok(())
+    }
+}
+
+/// Sequential and random throughput and IOPS for each block size of the profile
+#[derive(Default)]
+pub struct BlockSizeSweep {
+    target: Option<DiskTarget>,
+    file_size: usize,
+    test_file: Option<TempPath>,
+}
+
+impl Benchmark for BlockSizeSweep {
+    fn name(&self) -> &'static str {
+        "disk-sweep"
+    }
+
+    fn title(&self) -> &'static str {
+        "Disk Block-Size Sweep"
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        let target = resolve_target(ctx)?;
+        let profile = &ctx.settings.profile;
+        let largest = profile.sweep_block_sizes().last().copied().unwrap_or(DISK_CHUNK_SIZE);
+        self.file_size = file_size(ctx, &target, "Block-size sweep", profile.sweep_file_size(), largest)?;
+        self.test_file = Some(TempPath::new(target.test_file()));
+        self.target = Some(target);
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        let mut file = disk::create_test_file(prepared(&self.test_file)?, ctx.settings.direct_io)?;
+        let profile = &ctx.settings.profile;
+        let block_sizes = profile.sweep_block_sizes();
+        progress.start("Block-size sweep", block_sizes.len() as u64);
+        results.disk_block_sizes =
+            disk::sweep_block_sizes(&mut file, self.file_size, &block_sizes, profile.sweep_duration(), |done| {
+                progress.set_position(done as u64)
+            })?;
+        results.disk_target = self.target.clone();
+        Ok(())
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        self.test_file = None;
+        Ok(())
+    }
+}
+
+/// Random IOPS and throughput at each configured queue depth
+#[derive(Default)]
+pub struct QueueDepthBenchmark {
+    target: Option<DiskTarget>,
+    file_size: usize,
+    test_file: Option<TempPath>,
+}
+
+impl Benchmark for QueueDepthBenchmark {
+    fn name(&self) -> &'static str {
+        "disk-qd"
+    }
+
+    fn title(&self) -> &'static str {
+        "Disk Queue Depth Scaling"
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        let target = resolve_target(ctx)?;
+        let profile = &ctx.settings.profile;
+        self.file_size = file_size(ctx, &target, "Queue depth test", profile.qd_file_size(), profile.qd_block_size())?;
+        self.test_file = Some(TempPath::new(target.test_file()));
+        self.target = Some(target);
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        let settings = ctx.settings;
+        let mut file = disk::create_test_file(prepared(&self.test_file)?, settings.direct_io)?;
+        let job = disk::QueueDepthJob {
+            file_size: self.file_size,
+            block_size: settings.profile.qd_block_size(),
+            workers: settings.disk_workers,
+            io_uring: settings.io_uring,
+            duration: settings.profile.qd_duration(),
+        };
+        progress.start("Queue depths", settings.queue_depths.len() as u64);
+        results.disk_queue_depths =
+            disk::measure_queue_depths(&mut file, job, &settings.queue_depths, |done| progress.set_position(done as u64))?;
+        results.disk_target = self.target.clone();
+        Ok(())
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        self.test_file = None;
+        Ok(())
+    }
+}
+
+/// Small appends made durable with fsync and with fdatasync
+#[derive(Default)]
+pub struct DurableWriteBenchmark {
+    target: Option<DiskTarget>,
+    max_bytes: usize,
+    test_file: Option<TempPath>,
+}
+
+impl Benchmark for DurableWriteBenchmark {
+    fn name(&self) -> &'static str {
+        "disk-fsync"
+    }
+
+    fn title(&self) -> &'static str {
+        "Durable Write Latency"
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        let target = resolve_target(ctx)?;
+        let profile = &ctx.settings.profile;
+        self.max_bytes = file_size(ctx, &target, "Durable write test", profile.fsync_max_bytes(), profile.fsync_append_size())?;
+        self.test_file = Some(TempPath::new(target.test_file()));
+        self.target = Some(target);
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        let test_file = prepared(&self.test_file)?;
+        let profile = &ctx.settings.profile;
+        progress.start("Durable writes", SyncMethod::ALL.len() as u64);
+        results.disk_durable_writes = SyncMethod::ALL
+            .into_iter()
+            .enumerate()
+            .map(|(i, method)| {
+                progress.set_message(method.name());
+                let result = disk::run_durable_writes(
+                    test_file,
+                    profile.fsync_append_size(),
+                    profile.fsync_duration(),
+                    self.max_bytes,
+                    method,
+                );
+                progress.set_position(i as u64 + 1);
+                result
+            })
+            .collect::<io::Result<_>>()?;
+
+        for r in &results.disk_durable_writes {
+            let p99 = r.sync_latency.as_ref().map_or(0.0, |s| s.p99_us);
+            if r.method == SyncMethod::Fsync.name() {
+                results.disk_fsync_writes_per_sec = r.writes_per_sec;
+                results.disk_fsync_p99 = p99;
+            } else {
+                results.disk_fdatasync_writes_per_sec = r.writes_per_sec;
+                results.disk_fdatasync_p99 = p99;
+            }
+        }
+        results.disk_target = self.target.clone();
+        Ok(())
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        self.test_file = None;
+        Ok(())
+    }
+}
+
+/// Create, stat, rename and delete rates of many small files and directories
+#[derive(Default)]
+pub struct MetadataBenchmark {
+    target: Option<DiskTarget>,
+    metadata_dir: Option<TempPath>,
+}
+
+impl Benchmark for MetadataBenchmark {
+    fn name(&self) -> &'static str {
+        "disk-meta"
+    }
+
+    fn title(&self) -> &'static str {
+        "File-System Metadata"
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        let target = resolve_target(ctx)?;
+        self.metadata_dir = Some(TempPath::new(target.metadata_dir()));
+        self.target = Some(target);
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        progress.start("Metadata operations", disk::METADATA_OPERATIONS.len() as u64);
+        results.disk_metadata = disk::measure_metadata(prepared(&self.metadata_dir)?, ctx.settings.profile.disk_meta.ops, |done| {
+            progress.set_position(done as u64)
+        })?;
+
+        let rates: Vec<f64> = results.disk_metadata.iter().map(|r| r.ops_per_sec).collect();
+        if let [file_create, file_stat, file_rename, file_delete, dir_create, dir_stat, dir_rename, dir_delete] = rates[..] {
+            results.disk_meta_file_create = file_create;
+            results.disk_meta_file_stat = file_stat;
+            results.disk_meta_file_rename = file_rename;
+            results.disk_meta_file_delete = file_delete;
+            results.disk_meta_dir_create = dir_create;
+            results.disk_meta_dir_stat = dir_stat;
+            results.disk_meta_dir_rename = dir_rename;
+            results.disk_meta_dir_delete = dir_delete;
+        }
+        results.disk_target = self.target.clone();
+        Ok(())
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        self.metadata_dir = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+// Memory bandwidth, latency and STREAM kernels, and the cache hierarchy sweep
+
+use std::io;
+use std::time::Instant;
+
+use rand::Rng;
+use sysinfo::SystemExt;
+
+use super::{Benchmark, Context};
+use crate::{cancel, memory, preflight, BenchmarkResults, CHUNK_SIZE, MEMORY_MIN_TEST_SIZE, STREAM_MIN_ARRAY_LEN};
+
+/// Sequential read and write speed, dependent-load latency and STREAM bandwidth
+#[derive(Default)]
+pub struct MemoryBenchmark {
+    /// Bytes of the read/write buffer and the latency chain, fitted to the available memory
+    size: usize,
+    /// Elements of each STREAM array
+    stream_len: usize,
+}
+
+impl Benchmark for MemoryBenchmark {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn title(&self) -> &'static str {
+        "Memory Benchmark"
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        let profile = &ctx.settings.profile;
+        let (size, stream_len) = (profile.memory_size(), profile.stream_array_len());
+        self.size = preflight::memory(ctx, "Memory test", size, MEMORY_MIN_TEST_SIZE.min(size))?;
+        let stream_bytes = 3 * std::mem::size_of::<f64>();
+        self.stream_len = preflight::memory(
+            ctx,
+            "STREAM test",
+            stream_len * stream_bytes,
+            STREAM_MIN_ARRAY_LEN.min(stream_len) * stream_bytes,
+        )? / stream_bytes;
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        let size = self.size;
+        let chunks = (size / CHUNK_SIZE) as u64;
+        let mut data = vec![0u8; size];
+
+        progress.start("Write speed", chunks);
+        let write_start = Instant::now();
+        let mut rng = rand::thread_rng();
+        for (i, chunk) in data.chunks_mut(CHUNK_SIZE).enumerate() {
+            cancel::check()?;
+            // Fill with random data
+            for byte in chunk.iter_mut() {
+                *byte = rng.gen();
+            }
+            progress.set_position(i as u64 + 1);
+        }
+        results.memory_write_speed = size as f64 / write_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
+
+        progress.start("Read speed", chunks);
+        let read_start = Instant::now();
+        let mut checksum = 0u64;
+        for (i, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
+            cancel::check()?;
+            // Read and perform a simple checksum
+            for &byte in chunk {
+                checksum = checksum.wrapping_add(byte as u64);
+            }
+            std::hint::black_box(checksum);
+            progress.set_position(i as u64 + 1);
+        }
+        results.memory_read_speed = size as f64 / read_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
+
+        // Dependent loads along a random chain spanning the whole buffer
+        progress.start("Memory latency", 1);
+        drop(data);
+        let chain = memory::build_pointer_chain(size);
+        results.memory_latency = memory::chase_latency_ns(&chain, ctx.settings.profile.memory.latency_loads);
+        drop(chain);
+        progress.set_position(1);
+
+        // STREAM kernels on large f64 arrays, on one thread and on every logical CPU
+        progress.start("STREAM kernels", 1);
+        let stream = memory::measure_stream(self.stream_len, ctx.sys.cpus().len());
+        cancel::check()?;
+        progress.set_position(1);
+        let [copy, scale, add, triad] = [0, 1, 2, 3].map(|i| stream.get(i).map_or(0.0, |r| r.multi_thread_gbs));
+        results.stream_copy = copy;
+        results.stream_scale = scale;
+        results.stream_add = add;
+        results.stream_triad = triad;
+        results.stream = stream;
+        Ok(())
+    }
+}
+
+/// Latency and read bandwidth over growing working sets, and the cache levels they reveal
+#[derive(Default)]
+pub struct CacheSweep {
+    sizes: Vec<usize>,
+}
+
+impl Benchmark for CacheSweep {
+    fn name(&self) -> &'static str {
+        "cache"
+    }
+
+    fn title(&self) -> &'static str {
+        "Cache Hierarchy Sweep"
+    }
+
+    fn setup(&mut self, ctx: &mut Context) -> io::Result<()> {
+        // Stay well clear of swapping, which would distort the largest sizes
+        ctx.sys.refresh_memory();
+        let max_size = ctx.settings.profile.cache_max_size().min(ctx.sys.available_memory() as usize / 2);
+        self.sizes = memory::sweep_sizes(max_size);
+        Ok(())
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        let progress = ctx.progress;
+        progress.start(
+            &format!(
+                "Latency and read bandwidth from {} to {}",
+                memory::format_size(memory::SWEEP_MIN_SIZE),
+                memory::format_size(self.sizes.last().copied().unwrap_or(0))
+            ),
+            self.sizes.len() as u64,
+        );
+        let mut points = Vec::with_capacity(self.sizes.len());
+        for &size in &self.sizes {
+            cancel::check()?;
+            progress.set_message(&memory::format_size(size));
+            points.push(memory::measure_sweep_point(size));
+            progress.set_position(points.len() as u64);
+        }
+
+        results.cache_levels = memory::detect_levels(&points);
+        results.cache_sweep = points;
+        Ok(())
+    }
+}
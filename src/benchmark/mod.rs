@@ -0,0 +1,166 @@
+// The `Benchmark` trait every test implements, the context it runs in and the progress
+// callbacks through which a front-end shows how far it got
+
+mod cpu;
+mod disk;
+mod memory;
+
+use std::io;
+
+use sysinfo::System;
+
+use crate::profile::Profile;
+use crate::{BenchmarkResults, BenchmarkSettings};
+
+pub use self::cpu::CpuBenchmark;
+pub use self::disk::{BlockSizeSweep, DiskBenchmark, DurableWriteBenchmark, MetadataBenchmark, QueueDepthBenchmark};
+pub use self::memory::{CacheSweep, MemoryBenchmark};
+
+/// Receives progress from a running benchmark. Benchmarks never print; a front-end shows
+/// steps and notes however it likes.
+pub trait Progress {
+    /// A new step started, e.g. "Sequential write", made of `len` units of work
+    fn start(&self, step: &str, len: u64);
+    /// Units of the current step done so far
+    fn set_position(&self, position: u64);
+    /// What the current step works on right now, e.g. the thread count or block size
+    fn set_message(&self, _message: &str) {}
+    /// A test runs with less data than configured because memory or disk space is short
+    fn note(&self, _note: &str) {}
+    /// The next benchmark of a suite starts, e.g. "CPU Benchmark"
+    fn benchmark(&self, _title: &str) {}
+}
+
+/// Progress for callers that do not show any
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn start(&self, _step: &str, _len: u64) {}
+    fn set_position(&self, _position: u64) {}
+}
+
+/// What a benchmark runs with: the system it measures, the settings and where progress goes
+pub struct Context<'a> {
+    pub sys: &'a mut System,
+    pub settings: &'a BenchmarkSettings,
+    pub progress: &'a dyn Progress,
+    notes: Vec<String>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(sys: &'a mut System, settings: &'a BenchmarkSettings, progress: &'a dyn Progress) -> Self {
+        Self { sys, settings, progress, notes: Vec::new() }
+    }
+
+    /// Records a test that runs with less data than configured; the note ends up in
+    /// `BenchmarkResults::preflight_notes`
+    pub fn note(&mut self, note: String) {
+        self.progress.note(&note);
+        self.notes.push(note);
+    }
+}
+
+pub trait Benchmark {
+    /// Short name, e.g. "cpu" or "disk-qd"
+    fn name(&self) -> &'static str;
+
+    /// Name to show to people, e.g. "CPU Benchmark"
+    fn title(&self) -> &'static str;
+
+    /// Benchmarks a run of this one is listed as in `BenchmarkReport::benchmarks`, which
+    /// decides the metrics it is compared and summarized on
+    fn benchmarks(&self, _settings: &BenchmarkSettings) -> Vec<&'static str> {
+        vec![self.name()]
+    }
+
+    /// Checks that enough memory or disk space is available and prepares buffers or test
+    /// files; fails before any measurement when the test cannot run
+    fn setup(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Measures and fills in this benchmark's fields of `results`
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()>;
+
+    /// Releases what `setup` prepared, such as test files; called even when `run` failed
+    fn teardown(&mut self, _ctx: &mut Context) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Benchmarks run one after another as one: each is set up right before it runs and torn
+/// down right after, so only one holds memory or test files at a time
+pub struct Suite {
+    benchmarks: Vec<Box<dyn Benchmark>>,
+}
+
+impl Suite {
+    pub fn new(benchmarks: Vec<Box<dyn Benchmark>>) -> Self {
+        Self { benchmarks }
+    }
+
+    /// The tests a suite profile lists, in its order
+    pub fn from_profile(profile: &Profile) -> io::Result<Self> {
+        let benchmarks = profile
+            .tests
+            .iter()
+            .map(|name| {
+                by_name(name).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("unknown benchmark '{}'", name)))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self::new(benchmarks))
+    }
+}
+
+/// The benchmark with the short name `name`, e.g. "disk-qd"
+pub fn by_name(name: &str) -> Option<Box<dyn Benchmark>> {
+    let benchmark: Box<dyn Benchmark> = match name {
+        "cpu" => Box::new(CpuBenchmark),
+        "memory" => Box::new(MemoryBenchmark::default()),
+        "cache" => Box::new(CacheSweep::default()),
+        "disk" => Box::new(DiskBenchmark::default()),
+        "disk-sweep" => Box::new(BlockSizeSweep::default()),
+        "disk-qd" => Box::new(QueueDepthBenchmark::default()),
+        "disk-fsync" => Box::new(DurableWriteBenchmark::default()),
+        "disk-meta" => Box::new(MetadataBenchmark::default()),
+        _ => return None,
+    };
+    Some(benchmark)
+}
+
+impl Benchmark for Suite {
+    fn name(&self) -> &'static str {
+        "full"
+    }
+
+    fn title(&self) -> &'static str {
+        "Full System Benchmark"
+    }
+
+    fn benchmarks(&self, settings: &BenchmarkSettings) -> Vec<&'static str> {
+        self.benchmarks.iter().flat_map(|b| b.benchmarks(settings)).collect()
+    }
+
+    fn run(&mut self, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+        for benchmark in &mut self.benchmarks {
+            ctx.progress.benchmark(benchmark.title());
+            run_into(benchmark.as_mut(), ctx, results)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets up, runs and tears down `benchmark`, adding its results to `results`
+pub fn run_into(benchmark: &mut dyn Benchmark, ctx: &mut Context, results: &mut BenchmarkResults) -> io::Result<()> {
+    let outcome = benchmark.setup(ctx).and_then(|()| benchmark.run(ctx, results));
+    let teardown = benchmark.teardown(ctx);
+    results.preflight_notes.append(&mut ctx.notes);
+    outcome.and(teardown)
+}
+
+/// Runs `benchmark` once and returns its results
+pub fn run(benchmark: &mut dyn Benchmark, ctx: &mut Context) -> io::Result<BenchmarkResults> {
+    let mut results = BenchmarkResults::default();
+    run_into(benchmark, ctx, &mut results)?;
+    Ok(results)
+}
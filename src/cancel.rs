@@ -0,0 +1,34 @@
+// Cooperative cancellation of a running benchmark: the menu requests it from the UI thread
+// and the benchmark loops stop at their next check
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+pub fn request() {
+    CANCELLED.store(true, Ordering::Relaxed);
+}
+
+pub fn reset() {
+    CANCELLED.store(false, Ordering::Relaxed);
+}
+
+/// Whether the running benchmark should stop; duration-based loops treat this like their
+/// deadline and leave the error to `check`
+pub fn requested() -> bool {
+    CANCELLED.load(Ordering::Relaxed)
+}
+
+/// Fails with `ErrorKind::Interrupted` once cancellation was requested
+pub fn check() -> io::Result<()> {
+    if requested() {
+        return Err(io::Error::new(io::ErrorKind::Interrupted, "benchmark cancelled"));
+    }
+    Ok(())
+}
+
+/// Whether `error` comes from a cancelled benchmark
+pub fn is_cancellation(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::Interrupted && requested()
+}
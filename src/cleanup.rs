@@ -0,0 +1,91 @@
+// Removal of benchmark test files however the process ends: normally, through an error
+// or panic, or from an interrupt handler
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use sysinfo::{Pid, PidExt, System, SystemExt};
+
+use crate::disk::{temp_owner, TempOwner};
+
+/// Test files and directories that exist right now, for `remove_temp_paths`
+static TEMP_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+fn remove(path: &Path) {
+    let _ = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+}
+
+/// A test file or directory that is removed when the guard is dropped, including while
+/// unwinding from an error or panic, and by `remove_temp_paths`
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    pub fn new(path: PathBuf) -> Self {
+        if let Ok(mut paths) = TEMP_PATHS.lock() {
+            paths.push(path.clone());
+        }
+        Self(path)
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        remove(&self.0);
+        if let Ok(mut paths) = TEMP_PATHS.lock() {
+            paths.retain(|p| p != &self.0);
+        }
+    }
+}
+
+/// Removes every test file and directory whose `TempPath` is still alive, for an
+/// interrupt handler that exits without dropping them
+pub fn remove_temp_paths() {
+    let paths = TEMP_PATHS.lock().map(|paths| paths.clone()).unwrap_or_default();
+    for path in &paths {
+        remove(path);
+    }
+}
+
+/// Removes test files that an earlier run, killed before it could clean up, left in the
+/// current directory, the temporary directory or `disk_dir`, and returns their paths.
+/// Files whose owning process is still running belong to another instance and are kept;
+/// files named by earlier releases, which carry no PID, are always removed.
+pub fn remove_stale_files(disk_dir: Option<&Path>) -> Vec<PathBuf> {
+    let dirs = [std::env::current_dir().ok(), Some(std::env::temp_dir()), disk_dir.map(Path::to_path_buf)];
+    let mut sys = System::new();
+    let mut removed = Vec::new();
+    for dir in dirs.into_iter().flatten() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let stale = match entry.file_name().to_str().and_then(temp_owner) {
+                Some(TempOwner::Legacy) => true,
+                Some(TempOwner::Process(pid)) => pid != std::process::id() && !sys.refresh_process(Pid::from_u32(pid)),
+                None => false,
+            };
+            if !stale {
+                continue;
+            }
+            let path = entry.path();
+            remove(&path);
+            removed.push(path);
+        }
+    }
+    removed
+}
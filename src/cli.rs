@@ -0,0 +1,476 @@
+// Command-line interface for running benchmarks without the interactive menu
+
+use std::path::PathBuf;
+
+use crate::baseline::DEFAULT_TOLERANCE_PCT;
+use eonflux_benchmark::profile::Profile;
+use eonflux_benchmark::BenchmarkSettings;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_REGRESSION: i32 = 3;
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+pub const USAGE: &str = "\
+Usage: eonflux_benchmark [COMMAND] [OPTIONS]
+
+Run without a command to open the interactive menu.
+
+Commands:
+  sysinfo    Print system information
+  cpu        Run the CPU benchmark
+  memory     Run the memory benchmark
+  disk       Run the standard disk benchmark
+  full       Run the tests of the suite profile (default: CPU, memory and disk)
+             and record the run in the history
+  cache      Sweep working-set sizes to find the cache hierarchy
+  history    List recorded full benchmark runs
+  baseline   List saved baselines
+  help       Print this message
+
+Disk commands:
+  disk sweep              Measure sequential and random throughput and IOPS for block
+                          sizes from 4 KB to 16 MB
+  disk qd                 Measure 4 KB random IOPS and throughput at several queue depths
+  disk fsync              Measure durable writes per second and sync latency of small
+                          appends, each followed by fsync or fdatasync
+  disk meta               Measure create, stat, rename and delete operations per second
+                          on many small files and directories
+
+History commands:
+  history list            List recorded runs of this machine
+  history trend           Show how every metric changed over the recorded runs
+  history delete <ID>     Delete a recorded run
+  history tag <ID> <TAG>  Attach a tag to a recorded run
+  history untag <ID> <TAG>
+                          Remove a tag from a recorded run
+
+Baseline commands:
+  baseline list                List saved baselines
+  baseline save <NAME> [ID]    Save a recorded run (default: the latest) as a baseline
+  baseline delete <NAME>       Delete a saved baseline
+
+Options:
+  --suite <NAME|PATH>      Suite profile: quick, standard, extended, or a TOML or JSON
+                           file listing the tests of 'full' and the sizes, durations,
+                           threads, block sizes and target of every test
+                           (default: standard). Other options override the file.
+  --json <PATH>            Write the results and machine details to PATH as JSON
+  --save-baseline <NAME>   Save the results as a baseline
+  --baseline <NAME>        Compare the results with a baseline (a name or a JSON export)
+  --tolerance <PERCENT>    Allowed slowdown before a metric counts as a regression
+                           (default: 5)
+  --iterations <N>         Run each benchmark N times and report statistics (default: 1)
+  --warmup <N>             Discarded runs before the measured ones (default: 0)
+  --disk-dir <DIR>         Directory the disk benchmark writes its test file to
+                           (default: $EONFLUX_BENCHMARK_DISK_DIR, else the current directory)
+  --direct-io              Also run the sequential disk tests with the OS cache bypassed
+  --mix <READ%>            Read share of the mixed 4 KB random disk test, e.g. 70 or
+                           70/30 (default: 70)
+  --queue-depths <LIST>    Comma-separated queue depths for 'disk qd' (default: 1,4,32)
+  --workers <N>            Concurrent workers for 'disk qd', each with the full queue
+                           depth (default: 1)
+  --io-uring               Submit 'disk qd' I/O through io_uring (Linux, needs the
+                           io_uring build feature)
+  --high-priority          Run at a raised process priority (needs root on Linux and
+                           macOS)
+
+Exit status:
+  0    the command completed
+  1    a benchmark failed
+  2    the command line could not be parsed
+  3    a regression against the baseline was found
+  130  interrupted by Ctrl-C; the test files were removed";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryAction {
+    List,
+    Trend,
+    Delete(u32),
+    Tag(u32, String),
+    Untag(u32, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaselineAction {
+    List,
+    Save(String, Option<u32>),
+    Delete(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiskTest {
+    Standard,
+    BlockSizeSweep,
+    QueueDepth,
+    DurableWrites,
+    Metadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Menu,
+    SysInfo,
+    Cpu,
+    Memory,
+    Disk(DiskTest),
+    Full,
+    Cache,
+    History(HistoryAction),
+    Baseline(BaselineAction),
+    Help,
+}
+
+impl Command {
+    /// Whether the command runs benchmarks and so produces results
+    pub fn runs_benchmarks(&self) -> bool {
+        matches!(self, Command::Cpu | Command::Memory | Command::Disk(_) | Command::Full | Command::Cache)
+    }
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub json: Option<PathBuf>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub tolerance_pct: f64,
+    pub overrides: Overrides,
+    pub settings: BenchmarkSettings,
+}
+
+/// Returns the value of `--name value` or `--name=value`, if `arg` is that option.
+fn option_value<I: Iterator<Item = String>>(
+    name: &str,
+    arg: &str,
+    args: &mut I,
+) -> Result<Option<String>, String> {
+    if arg == name {
+        return args
+            .next()
+            .map(Some)
+            .ok_or_else(|| format!("option '{}' requires a value", name));
+    }
+    Ok(arg
+        .strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix('='))
+        .map(str::to_string))
+}
+
+fn count(option: &str, value: &str, min: usize) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!("invalid value '{}' for '{}'", value, option)),
+    }
+}
+
+/// Parses "70" or "70/30" as a read percentage
+fn mixed_read_pct(value: &str) -> Result<u32, String> {
+    let invalid = || format!("invalid read/write mix '{}'", value);
+    let (read, write) = match value.split_once('/') {
+        Some((read, write)) => (read.trim().parse::<u32>(), Some(write.trim().parse::<u32>())),
+        None => (value.trim().parse::<u32>(), None),
+    };
+    match (read, write) {
+        (Ok(read), None) if read <= 100 => Ok(read),
+        (Ok(read), Some(Ok(write))) if read + write == 100 => Ok(read),
+        _ => Err(invalid()),
+    }
+}
+
+fn required<I: Iterator<Item = String>>(args: &mut I, what: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("missing {}", what))
+}
+
+fn run_id<I: Iterator<Item = String>>(args: &mut I) -> Result<u32, String> {
+    let id = required(args, "run ID")?;
+    id.parse().map_err(|_| format!("invalid run ID '{}'", id))
+}
+
+fn parse_disk<I: Iterator<Item = String>>(args: &mut I) -> Result<DiskTest, String> {
+    match args.next().as_deref() {
+        None => Ok(DiskTest::Standard),
+        Some("sweep") => Ok(DiskTest::BlockSizeSweep),
+        Some("qd") => Ok(DiskTest::QueueDepth),
+        Some("fsync") => Ok(DiskTest::DurableWrites),
+        Some("meta") => Ok(DiskTest::Metadata),
+        Some(other) => Err(format!("unknown disk test '{}'", other)),
+    }
+}
+
+fn parse_history<I: Iterator<Item = String>>(args: &mut I) -> Result<HistoryAction, String> {
+    match args.next().as_deref() {
+        None | Some("list") => Ok(HistoryAction::List),
+        Some("trend") => Ok(HistoryAction::Trend),
+        Some("delete") => Ok(HistoryAction::Delete(run_id(args)?)),
+        Some("tag") => Ok(HistoryAction::Tag(run_id(args)?, required(args, "tag")?)),
+        Some("untag") => Ok(HistoryAction::Untag(run_id(args)?, required(args, "tag")?)),
+        Some(other) => Err(format!("unknown history command '{}'", other)),
+    }
+}
+
+fn parse_baseline<I: Iterator<Item = String>>(args: &mut I) -> Result<BaselineAction, String> {
+    match args.next().as_deref() {
+        None | Some("list") => Ok(BaselineAction::List),
+        Some("save") => {
+            let name = required(args, "baseline name")?;
+            let mut rest = args.peekable();
+            let id = match rest.peek() {
+                Some(_) => Some(run_id(&mut rest)?),
+                None => None,
+            };
+            Ok(BaselineAction::Save(name, id))
+        }
+        Some("delete") => Ok(BaselineAction::Delete(required(args, "baseline name")?)),
+        Some(other) => Err(format!("unknown baseline command '{}'", other)),
+    }
+}
+
+/// Run settings given as options, which take precedence over the suite profile
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub iterations: Option<usize>,
+    pub warmup_iterations: Option<usize>,
+    pub disk_dir: Option<PathBuf>,
+    pub direct_io: bool,
+    pub mixed_read_pct: Option<u32>,
+    pub queue_depths: Option<Vec<usize>>,
+    pub disk_workers: Option<usize>,
+    pub io_uring: bool,
+    pub high_priority: bool,
+}
+
+impl Overrides {
+    /// The settings of `profile` with these options on top, starting from the defaults and
+    /// $EONFLUX_BENCHMARK_DISK_DIR, so nothing carries over from an earlier profile
+    pub fn settings(&self, profile: Profile) -> BenchmarkSettings {
+        let mut settings = BenchmarkSettings {
+            disk_dir: std::env::var_os("EONFLUX_BENCHMARK_DISK_DIR").map(PathBuf::from),
+            ..BenchmarkSettings::default()
+        };
+        settings.apply_profile(profile);
+        if let Some(iterations) = self.iterations {
+            settings.iterations = iterations;
+        }
+        if let Some(warmup_iterations) = self.warmup_iterations {
+            settings.warmup_iterations = warmup_iterations;
+        }
+        if let Some(dir) = &self.disk_dir {
+            settings.disk_dir = Some(dir.clone());
+        }
+        if let Some(pct) = self.mixed_read_pct {
+            settings.mixed_read_pct = pct;
+        }
+        if let Some(depths) = &self.queue_depths {
+            settings.queue_depths = depths.clone();
+        }
+        if let Some(workers) = self.disk_workers {
+            settings.disk_workers = workers;
+        }
+        settings.direct_io |= self.direct_io;
+        settings.io_uring |= self.io_uring;
+        settings.high_priority |= self.high_priority;
+        settings
+    }
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Cli, String> {
+    let mut positional = Vec::new();
+    let mut json = None;
+    let mut save_baseline = None;
+    let mut baseline = None;
+    let mut tolerance_pct = None;
+    let mut overrides = Overrides::default();
+    let mut help = false;
+    let mut profile = Profile::standard();
+    let args: Vec<String> = args.into_iter().collect();
+
+    // The suite profile comes first so that the other options override what it sets
+    let mut scan = args.iter().cloned();
+    while let Some(arg) = scan.next() {
+        if let Some(spec) = option_value("--suite", &arg, &mut scan)? {
+            profile = Profile::load(&spec).map_err(|e| format!("invalid suite profile '{}': {}", spec, e))?;
+        }
+    }
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if option_value("--suite", &arg, &mut args)?.is_some() {
+            // Applied above
+        } else if let Some(path) = option_value("--json", &arg, &mut args)? {
+            json = Some(PathBuf::from(path));
+        } else if let Some(name) = option_value("--save-baseline", &arg, &mut args)? {
+            save_baseline = Some(name);
+        } else if let Some(name) = option_value("--baseline", &arg, &mut args)? {
+            baseline = Some(name);
+        } else if let Some(value) = option_value("--tolerance", &arg, &mut args)? {
+            match value.trim_end_matches('%').parse::<f64>() {
+                Ok(pct) if pct >= 0.0 => tolerance_pct = Some(pct),
+                _ => return Err(format!("invalid tolerance '{}'", value)),
+            }
+        } else if let Some(value) = option_value("--iterations", &arg, &mut args)? {
+            overrides.iterations = Some(count("--iterations", &value, 1)?);
+        } else if let Some(value) = option_value("--warmup", &arg, &mut args)? {
+            overrides.warmup_iterations = Some(count("--warmup", &value, 0)?);
+        } else if let Some(dir) = option_value("--disk-dir", &arg, &mut args)? {
+            overrides.disk_dir = Some(PathBuf::from(dir));
+        } else if let Some(value) = option_value("--queue-depths", &arg, &mut args)? {
+            overrides.queue_depths = Some(
+                value
+                    .split(',')
+                    .map(|depth| count("--queue-depths", depth.trim(), 1))
+                    .collect::<Result<_, _>>()?,
+            );
+        } else if let Some(value) = option_value("--workers", &arg, &mut args)? {
+            overrides.disk_workers = Some(count("--workers", &value, 1)?);
+        } else if let Some(value) = option_value("--mix", &arg, &mut args)? {
+            overrides.mixed_read_pct = Some(mixed_read_pct(&value)?);
+        } else if arg == "--io-uring" {
+            if !eonflux_benchmark::disk::IO_URING_AVAILABLE {
+                return Err("io_uring support is not available in this build (Linux only, build with --features io_uring)".to_string());
+            }
+            overrides.io_uring = true;
+        } else if arg == "--high-priority" {
+            overrides.high_priority = true;
+        } else if arg == "--direct-io" {
+            overrides.direct_io = true;
+        } else if arg == "-h" || arg == "--help" {
+            help = true;
+        } else if arg.starts_with('-') {
+            return Err(format!("unknown option '{}'", arg));
+        } else {
+            positional.push(arg);
+        }
+    }
+    let settings = overrides.settings(profile);
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next().as_deref() {
+        // -h and --help show the usage whatever else is given
+        _ if help => Command::Help,
+        None => Command::Menu,
+        Some("sysinfo") => Command::SysInfo,
+        Some("cpu") => Command::Cpu,
+        Some("memory") => Command::Memory,
+        Some("disk") => Command::Disk(parse_disk(&mut positional)?),
+        Some("full") => Command::Full,
+        Some("cache") => Command::Cache,
+        Some("history") => Command::History(parse_history(&mut positional)?),
+        Some("baseline") => Command::Baseline(parse_baseline(&mut positional)?),
+        Some("help") => Command::Help,
+        Some(other) => return Err(format!("unknown command '{}'", other)),
+    };
+    if let Some(extra) = positional.next().filter(|_| !help) {
+        return Err(format!("unexpected argument '{}'", extra));
+    }
+
+    if !command.runs_benchmarks() && command != Command::Help {
+        for (name, set) in [
+            // sysinfo exports the machine details
+            ("--json", json.is_some() && command != Command::SysInfo),
+            ("--save-baseline", save_baseline.is_some()),
+            ("--baseline", baseline.is_some()),
+            ("--tolerance", tolerance_pct.is_some()),
+        ] {
+            if set {
+                return Err(format!("option '{}' requires a benchmark command", name));
+            }
+        }
+    }
+
+    Ok(Cli {
+        command,
+        json,
+        save_baseline,
+        baseline,
+        tolerance_pct: tolerance_pct.unwrap_or(DEFAULT_TOLERANCE_PCT),
+        overrides,
+        settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Cli, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn command(args: &[&str]) -> Command {
+        parse_args(args).unwrap().command
+    }
+
+    #[test]
+    fn parses_commands_and_subcommands() {
+        assert_eq!(command(&[]), Command::Menu);
+        assert_eq!(command(&["cpu"]), Command::Cpu);
+        assert_eq!(command(&["disk"]), Command::Disk(DiskTest::Standard));
+        assert_eq!(command(&["disk", "sweep"]), Command::Disk(DiskTest::BlockSizeSweep));
+        assert_eq!(command(&["disk", "qd"]), Command::Disk(DiskTest::QueueDepth));
+        assert_eq!(command(&["history"]), Command::History(HistoryAction::List));
+        assert_eq!(command(&["history", "tag", "3", "new-ssd"]), Command::History(HistoryAction::Tag(3, "new-ssd".to_string())));
+        assert_eq!(command(&["baseline", "save", "before"]), Command::Baseline(BaselineAction::Save("before".to_string(), None)));
+        assert_eq!(command(&["baseline", "save", "before", "7"]), Command::Baseline(BaselineAction::Save("before".to_string(), Some(7))));
+        assert_eq!(command(&["--help"]), Command::Help);
+        assert_eq!(command(&["cpu", "--help"]), Command::Help);
+        assert_eq!(command(&["disk", "qd", "-h"]), Command::Help);
+    }
+
+    #[test]
+    fn rejects_unknown_commands_options_and_arguments() {
+        assert!(parse_args(&["gpu"]).is_err());
+        assert!(parse_args(&["disk", "latency"]).is_err());
+        assert!(parse_args(&["cpu", "--fast"]).is_err());
+        assert!(parse_args(&["cpu", "extra"]).is_err());
+        assert!(parse_args(&["history", "delete", "latest"]).is_err());
+        assert!(parse_args(&["cpu", "--iterations"]).is_err());
+        assert!(parse_args(&["--json", "out.json"]).is_err());
+        assert!(parse_args(&["history", "--json", "out.json"]).is_err());
+        assert!(parse_args(&["baseline", "list", "--json", "out.json"]).is_err());
+        assert!(parse_args(&["sysinfo", "--json", "out.json"]).is_ok());
+        assert!(parse_args(&["sysinfo", "--baseline", "before"]).is_err());
+    }
+
+    #[test]
+    fn parses_the_read_write_mix() {
+        let mix = |value: &str| parse_args(&["disk", "--mix", value]).map(|cli| cli.settings.mixed_read_pct);
+        assert_eq!(mix("70"), Ok(70));
+        assert_eq!(mix("30/70"), Ok(30));
+        assert_eq!(mix("100"), Ok(100));
+        assert!(mix("101").is_err());
+        assert!(mix("70/40").is_err());
+        assert!(mix("most").is_err());
+        assert_eq!(parse_args(&["disk", "--mix=50/50"]).unwrap().settings.mixed_read_pct, 50);
+    }
+
+    #[test]
+    fn parses_queue_depth_lists() {
+        let depths = |value: &str| parse_args(&["disk", "qd", "--queue-depths", value]).map(|cli| cli.settings.queue_depths);
+        assert_eq!(depths("1,4,32"), Ok(vec![1, 4, 32]));
+        assert_eq!(depths("8, 16"), Ok(vec![8, 16]));
+        assert!(depths("1,0").is_err());
+        assert!(depths("1,,4").is_err());
+        assert!(depths("four").is_err());
+    }
+
+    #[test]
+    fn switching_profiles_starts_from_the_defaults() {
+        let cli = parse_args(&["--workers", "2"]).unwrap();
+        let extended = cli.overrides.settings(Profile::load("extended").unwrap());
+        assert_eq!((extended.iterations, extended.warmup_iterations), (3, 1));
+
+        let quick = cli.overrides.settings(Profile::load("quick").unwrap());
+        assert_eq!((quick.iterations, quick.warmup_iterations), (1, 0));
+        assert_eq!(quick.queue_depths, BenchmarkSettings::default().queue_depths);
+        assert_eq!(quick.profile.name, "quick");
+        assert_eq!(quick.disk_workers, 2, "options from the command line still apply");
+    }
+
+    #[test]
+    fn options_take_precedence_over_the_profile() {
+        let cli = parse_args(&["full", "--suite", "extended", "--iterations", "5"]).unwrap();
+        assert_eq!((cli.settings.iterations, cli.settings.warmup_iterations), (5, 1));
+    }
+}
@@ -0,0 +1,96 @@
+// Multi-threaded CPU workload and thread scaling measurement
+
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::cancel;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CpuScalingPoint {
+    pub threads: usize,
+    pub ops_per_sec: f64,
+    /// Throughput relative to a single thread
+    pub speedup: f64,
+    /// Speedup divided by thread count, in percent; 100% is perfect scaling
+    pub efficiency_pct: f64,
+}
+
+/// 1, 2, 4 … up to and always including `max_threads`
+pub fn thread_counts(max_threads: usize) -> Vec<usize> {
+    let max_threads = max_threads.max(1);
+    let mut counts = Vec::new();
+    let mut threads = 1;
+    while threads < max_threads {
+        counts.push(threads);
+        threads *= 2;
+    }
+    counts.push(max_threads);
+    counts
+}
+
+/// One unit of work: a chain of transcendental operations on a random seed
+fn operation(rng: &mut impl Rng) {
+    let n: f64 = rng.gen();
+    black_box((0..1000).fold(n, |acc, _| (acc.sqrt().sin() * acc.cos()).exp()));
+}
+
+/// Runs the workload on `threads` threads for `duration` and returns the total operations
+/// per second. `on_tick` receives the elapsed fraction of the run while workers are busy.
+pub fn run_workload(threads: usize, duration: Duration, mut on_tick: impl FnMut(f64)) -> f64 {
+    let start = Instant::now();
+    let operations: u64 = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut rng = rand::thread_rng();
+                    let mut operations = 0u64;
+                    while start.elapsed() < duration && !cancel::requested() {
+                        operation(&mut rng);
+                        operations += 1;
+                    }
+                    operations
+                })
+            })
+            .collect();
+
+        while start.elapsed() < duration && !cancel::requested() {
+            on_tick(start.elapsed().as_secs_f64() / duration.as_secs_f64());
+            thread::sleep(PROGRESS_INTERVAL);
+        }
+        workers.into_iter().map(|w| w.join().unwrap_or(0)).sum()
+    });
+    operations as f64 / start.elapsed().as_secs_f64()
+}
+
+/// Runs the workload for every thread count up to `max_threads`, `duration` each; stops
+/// early when the benchmark is cancelled. `on_progress` receives the thread count being
+/// measured and the fraction of the whole measurement done.
+pub fn measure_scaling(max_threads: usize, duration: Duration, mut on_progress: impl FnMut(usize, f64)) -> Vec<CpuScalingPoint> {
+    let counts = thread_counts(max_threads);
+    let steps = counts.len() as f64;
+    let mut points: Vec<CpuScalingPoint> = Vec::with_capacity(counts.len());
+
+    for (step, &threads) in counts.iter().enumerate() {
+        if cancel::requested() {
+            break;
+        }
+        let ops_per_sec = run_workload(threads, duration, |fraction| {
+            on_progress(threads, (step as f64 + fraction) / steps);
+        });
+        let single = points.first().map_or(ops_per_sec, |p| p.ops_per_sec);
+        let speedup = if single > 0.0 { ops_per_sec / single } else { 0.0 };
+        points.push(CpuScalingPoint {
+            threads,
+            ops_per_sec,
+            speedup,
+            efficiency_pct: speedup / threads as f64 * 100.0,
+        });
+    }
+    points
+}
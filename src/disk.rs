@@ -0,0 +1,837 @@
+// Disk benchmark target selection, the volume it lives on, cache-bypassing file I/O and
+// the block-size sweep
+
+use std::alloc::{self, Layout};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Disk, DiskExt, System, SystemExt};
+
+use crate::cancel;
+use crate::latency::{self, LatencySummary};
+use crate::platform;
+
+/// Test files and the metadata scratch directory are named `<prefix>.<pid>.tmp` after
+/// the process that writes them, so runs sharing a directory never touch each other's
+/// files and a leftover can be traced to the run that left it
+pub const TEST_FILE_PREFIX: &str = "disk_benchmark_test";
+/// Scratch directory the metadata benchmark creates its files and directories in
+pub const METADATA_DIR_PREFIX: &str = "disk_benchmark_metadata";
+const TEMP_EXTENSION: &str = "tmp";
+
+fn temp_name(prefix: &str, pid: u32) -> String {
+    format!("{}.{}.{}", prefix, pid, TEMP_EXTENSION)
+}
+
+/// Who owns a test file or metadata directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempOwner {
+    Process(u32),
+    /// Named `<prefix>.tmp` by a release from before names carried the PID, so no
+    /// running instance of this version can own it
+    Legacy,
+}
+
+/// The owner of a test file or metadata directory, from its name; None for any other name
+pub fn temp_owner(name: &str) -> Option<TempOwner> {
+    let rest = [TEST_FILE_PREFIX, METADATA_DIR_PREFIX]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix)?.strip_prefix('.'))?;
+    if rest == TEMP_EXTENSION {
+        return Some(TempOwner::Legacy);
+    }
+    rest.strip_suffix(TEMP_EXTENSION)?.strip_suffix('.')?.parse().ok().map(TempOwner::Process)
+}
+
+/// Direct I/O needs buffers, offsets and lengths aligned to the logical block size of the
+/// device; 4 KB covers every common drive
+pub const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+/// Directory the disk benchmark writes to, labelled with the volume that holds it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiskTarget {
+    pub directory: PathBuf,
+    pub mount_point: PathBuf,
+    pub file_system: String,
+    pub disk_name: String,
+}
+
+/// Drops the `\\?\` prefix Windows adds to canonical paths, which mount points lack
+fn without_verbatim_prefix(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path,
+    }
+}
+
+/// The volume with the longest mount point containing `path`
+pub fn volume_of<'a>(sys: &'a System, path: &Path) -> Option<&'a Disk> {
+    sys.disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+impl DiskTarget {
+    /// Resolves `directory`, or the current directory when it is None
+    pub fn resolve(sys: &System, directory: Option<&Path>) -> io::Result<Self> {
+        let directory = match directory {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+        if !directory.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("disk target '{}' is not a directory", directory.display()),
+            ));
+        }
+        let directory = without_verbatim_prefix(directory.canonicalize()?);
+
+        let volume = volume_of(sys, &directory);
+        Ok(Self {
+            mount_point: volume.map_or_else(PathBuf::new, |d| d.mount_point().to_path_buf()),
+            file_system: volume.map_or_else(
+                || "unknown".to_string(),
+                |d| String::from_utf8_lossy(d.file_system()).into_owned(),
+            ),
+            disk_name: volume.map_or_else(String::new, |d| d.name().to_string_lossy().into_owned()),
+            directory,
+        })
+    }
+
+    pub fn test_file(&self) -> PathBuf {
+        self.directory.join(temp_name(TEST_FILE_PREFIX, std::process::id()))
+    }
+
+    pub fn metadata_dir(&self) -> PathBuf {
+        self.directory.join(temp_name(METADATA_DIR_PREFIX, std::process::id()))
+    }
+
+    /// e.g. "/home/user (mounted at /home, ext4 on /dev/nvme0n1p3)"
+    pub fn label(&self) -> String {
+        if self.mount_point.as_os_str().is_empty() {
+            return format!("{} (unknown volume)", self.directory.display());
+        }
+        format!(
+            "{} (mounted at {}, {} on {})",
+            self.directory.display(),
+            self.mount_point.display(),
+            self.file_system,
+            self.disk_name
+        )
+    }
+}
+
+/// A writable place for the test file on the volume mounted at `mount_point`: the current
+/// or temporary directory if either is on that volume, otherwise the mount point itself
+pub fn directory_on(sys: &System, mount_point: &Path) -> PathBuf {
+    let candidates = [std::env::current_dir().ok(), Some(std::env::temp_dir())];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| dir.canonicalize().ok().map(without_verbatim_prefix))
+        .find(|dir| volume_of(sys, dir).is_some_and(|d| d.mount_point() == mount_point))
+        .unwrap_or_else(|| mount_point.to_path_buf())
+}
+
+/// Heap buffer aligned to `DIRECT_IO_ALIGNMENT`, as cache-bypassing reads and writes require
+pub struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl AlignedBuffer {
+    pub fn new(len: usize) -> Self {
+        let layout = Layout::from_size_align(len.max(1), DIRECT_IO_ALIGNMENT).expect("invalid buffer layout");
+        // SAFETY: the layout has a non-zero size
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        Self { ptr, layout }
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the allocation is `layout.size()` initialized bytes owned by `self`
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as in `deref`, and `&mut self` guarantees exclusive access
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        // SAFETY: allocated in `new` with this layout
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+/// Creates `path` for reading and writing with the OS page cache bypassed: O_DIRECT on
+/// Linux, F_NOCACHE on macOS and FILE_FLAG_NO_BUFFERING on Windows
+pub fn create_uncached(path: &Path) -> io::Result<File> {
+    platform::open_uncached(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "could not open {} with {} (the file system may not support it): {}",
+                path.display(),
+                platform::UNCACHED_IO,
+                e
+            ),
+        )
+    })
+}
+
+/// Creates the test file, bypassing the page cache when `direct` is set
+pub fn create_test_file(path: &Path, direct: bool) -> io::Result<File> {
+    if direct {
+        return create_uncached(path);
+    }
+    OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)
+}
+
+/// Speed and per-chunk latency of one sequential pass over the test file
+pub struct SequentialPass {
+    pub mb_per_sec: f64,
+    pub latency: Option<LatencySummary>,
+}
+
+/// Writes `size` bytes of random data to `file` in `chunk_size` blocks and flushes them to
+/// the device. `on_step` is called with the number of chunks written.
+pub fn sequential_write(file: &mut File, size: usize, chunk_size: usize, mut on_step: impl FnMut(usize)) -> io::Result<SequentialPass> {
+    let mut rng = rand::thread_rng();
+    let mut buffer = vec![0u8; chunk_size];
+    let mut latencies = Vec::with_capacity(size / chunk_size);
+
+    let start = Instant::now();
+    for i in 0..size / chunk_size {
+        cancel::check()?;
+        rng.fill(&mut buffer[..]);
+        let io_start = Instant::now();
+        file.write_all(&buffer)?;
+        latencies.push(io_start.elapsed());
+        on_step(i + 1);
+    }
+    file.sync_all()?;
+    Ok(SequentialPass {
+        mb_per_sec: size as f64 / start.elapsed().as_secs_f64() / 1024.0 / 1024.0,
+        latency: latency::summarize(&latencies),
+    })
+}
+
+/// Reads the first `size` bytes of `file` back in `chunk_size` blocks. `on_step` is called
+/// with the number of chunks read.
+pub fn sequential_read(file: &mut File, size: usize, chunk_size: usize, mut on_step: impl FnMut(usize)) -> io::Result<SequentialPass> {
+    let mut buffer = vec![0u8; chunk_size];
+    let mut latencies = Vec::with_capacity(size / chunk_size);
+    file.seek(SeekFrom::Start(0))?;
+
+    let start = Instant::now();
+    for i in 0..size / chunk_size {
+        cancel::check()?;
+        let io_start = Instant::now();
+        file.read_exact(&mut buffer)?;
+        latencies.push(io_start.elapsed());
+        on_step(i + 1);
+    }
+    Ok(SequentialPass {
+        mb_per_sec: size as f64 / start.elapsed().as_secs_f64() / 1024.0 / 1024.0,
+        latency: latency::summarize(&latencies),
+    })
+}
+
+/// Writes `size` bytes to `path` in `chunk_size` blocks and reads them back, both with the
+/// page cache bypassed. Returns the write and read speeds in MB/s. `on_step` is called
+/// with the number of chunks written and then read, out of twice the chunk count.
+pub fn sequential_uncached(path: &Path, size: usize, chunk_size: usize, mut on_step: impl FnMut(usize)) -> io::Result<(f64, f64)> {
+    let mut file = create_uncached(path)?;
+    let mut buffer = AlignedBuffer::new(chunk_size);
+    let mut rng = rand::thread_rng();
+    let chunks = size / chunk_size;
+
+    let write_start = Instant::now();
+    for i in 0..chunks {
+        cancel::check()?;
+        rng.fill(&mut buffer[..]);
+        file.write_all(&buffer)?;
+        on_step(i + 1);
+    }
+    file.sync_all()?;
+    let write_speed = (chunks * chunk_size) as f64 / write_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
+
+    file.seek(SeekFrom::Start(0))?;
+    let read_start = Instant::now();
+    for i in 0..chunks {
+        cancel::check()?;
+        file.read_exact(&mut buffer)?;
+        on_step(chunks + i + 1);
+    }
+    let read_speed = (chunks * chunk_size) as f64 / read_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
+    Ok((write_speed, read_speed))
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Throughput {
+    pub mb_per_sec: f64,
+    pub iops: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BlockSizePoint {
+    pub block_size: usize,
+    pub sequential_read: Throughput,
+    pub sequential_write: Throughput,
+    pub random_read: Throughput,
+    pub random_write: Throughput,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Sequential,
+    Random,
+}
+
+/// Reads or writes `block_size` blocks of the first `file_size` bytes of `file` until every
+/// block was touched once or `duration` is up. Writes are flushed to the device before the
+/// clock stops.
+fn run_blocks(
+    file: &mut File,
+    buffer: &mut [u8],
+    file_size: usize,
+    access: Access,
+    write: bool,
+    duration: Duration,
+) -> io::Result<Throughput> {
+    let block_size = buffer.len();
+    let blocks = file_size / block_size;
+    let mut rng = rand::thread_rng();
+    let mut operations = 0;
+
+    let start = Instant::now();
+    while operations < blocks && start.elapsed() < duration {
+        cancel::check()?;
+        let block = match access {
+            Access::Sequential => operations,
+            Access::Random => rng.gen_range(0..blocks),
+        };
+        file.seek(SeekFrom::Start((block * block_size) as u64))?;
+        if write {
+            file.write_all(buffer)?;
+        } else {
+            file.read_exact(buffer)?;
+        }
+        operations += 1;
+    }
+    if write {
+        file.sync_data()?;
+    }
+    let secs = start.elapsed().as_secs_f64();
+    Ok(Throughput {
+        mb_per_sec: (operations * block_size) as f64 / secs / 1024.0 / 1024.0,
+        iops: operations as f64 / secs,
+    })
+}
+
+/// Writes `size` bytes to `file` by repeating `buffer`, the last copy cut short as needed,
+/// and flushes them to the device
+fn fill(file: &mut File, buffer: &[u8], size: usize) -> io::Result<()> {
+    let mut written = 0;
+    while written < size {
+        cancel::check()?;
+        let len = buffer.len().min(size - written);
+        file.write_all(&buffer[..len])?;
+        written += len;
+    }
+    file.sync_all()
+}
+
+/// Fills `file` with `file_size` bytes of random data, then measures sequential and random
+/// reads and writes at each of `block_sizes` for up to `duration` each. `on_step` is called
+/// with the number of block sizes done.
+pub fn sweep_block_sizes(
+    file: &mut File,
+    file_size: usize,
+    block_sizes: &[usize],
+    duration: Duration,
+    mut on_step: impl FnMut(usize),
+) -> io::Result<Vec<BlockSizePoint>> {
+    let largest = block_sizes.iter().copied().max().unwrap_or(DIRECT_IO_ALIGNMENT);
+    let mut buffer = AlignedBuffer::new(largest);
+    rand::thread_rng().fill(&mut buffer[..]);
+    fill(file, &buffer, file_size)?;
+
+    let mut points = Vec::with_capacity(block_sizes.len());
+    for (step, &block_size) in block_sizes.iter().enumerate() {
+        let block = &mut buffer[..block_size];
+        let mut run = |access, write| run_blocks(file, block, file_size, access, write, duration);
+        points.push(BlockSizePoint {
+            block_size,
+            sequential_write: run(Access::Sequential, true)?,
+            sequential_read: run(Access::Sequential, false)?,
+            random_write: run(Access::Random, true)?,
+            random_read: run(Access::Random, false)?,
+        });
+        on_step(step + 1);
+    }
+    Ok(points)
+}
+
+/// Whether io_uring support was compiled in (Linux with the `io_uring` feature)
+pub const IO_URING_AVAILABLE: bool = cfg!(all(target_os = "linux", feature = "io_uring"));
+
+/// Whole `block_size` blocks in a `file_size` test file, or an error when it holds none
+/// and random I/O would have no block to pick
+fn blocks_in(file_size: usize, block_size: usize, test: &str) -> io::Result<usize> {
+    match file_size.checked_div(block_size) {
+        Some(blocks) if blocks > 0 => Ok(blocks),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "the {} file of {} is smaller than one {} block",
+                test,
+                crate::memory::format_size(file_size),
+                crate::memory::format_size(block_size)
+            ),
+        )),
+    }
+}
+
+/// Shape of the queue-depth test, shared by every queue depth
+#[derive(Clone, Copy)]
+pub struct QueueDepthJob {
+    /// Bytes of the test file that random I/O is spread over
+    pub file_size: usize,
+    pub block_size: usize,
+    pub workers: usize,
+    pub io_uring: bool,
+    /// Run time per queue depth and direction
+    pub duration: Duration,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QueueDepthPoint {
+    /// Outstanding I/Os per worker
+    pub queue_depth: usize,
+    pub workers: usize,
+    pub random_read: Throughput,
+    pub random_write: Throughput,
+}
+
+/// Keeps `depth` random `block_size` I/Os in flight by running one synchronous thread
+/// per outstanding I/O; returns the number of completed I/Os
+fn threaded_worker(file: &File, job: QueueDepthJob, depth: usize, write: bool, deadline: Instant) -> io::Result<u64> {
+    let QueueDepthJob { file_size, block_size, .. } = job;
+    let blocks = file_size / block_size;
+    std::thread::scope(|scope| {
+        let slots: Vec<_> = (0..depth)
+            .map(|_| {
+                scope.spawn(move || -> io::Result<u64> {
+                    let mut buffer = AlignedBuffer::new(block_size);
+                    let mut rng = rand::thread_rng();
+                    rng.fill(&mut buffer[..]);
+                    let mut completed = 0;
+                    while Instant::now() < deadline {
+                        cancel::check()?;
+                        let offset = (rng.gen_range(0..blocks) * block_size) as u64;
+                        if write {
+                            platform::write_at(file, &buffer, offset)?;
+                        } else {
+                            platform::read_at(file, &mut buffer, offset)?;
+                        }
+                        completed += 1;
+                    }
+                    Ok(completed)
+                })
+            })
+            .collect();
+        slots.into_iter().map(|slot| slot.join().unwrap_or_else(|_| Err(worker_panicked()))).sum()
+    })
+}
+
+fn worker_panicked() -> io::Error {
+    io::Error::other("a queue-depth worker thread panicked")
+}
+
+/// Keeps `depth` random `block_size` I/Os in flight from a single thread through an
+/// io_uring submission queue; returns the number of completed I/Os
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+fn uring_worker(file: &File, job: QueueDepthJob, depth: usize, write: bool, deadline: Instant) -> io::Result<u64> {
+    use io_uring::{opcode, types, IoUring};
+    use std::os::unix::io::AsRawFd;
+
+    let QueueDepthJob { file_size, block_size, .. } = job;
+    // Declared before the ring so that they are dropped after it
+    let mut buffers: Vec<AlignedBuffer> = (0..depth).map(|_| AlignedBuffer::new(block_size)).collect();
+    let mut ring = IoUring::new(depth.next_power_of_two() as u32)?;
+    let fd = types::Fd(file.as_raw_fd());
+    let blocks = file_size / block_size;
+    let mut rng = rand::thread_rng();
+
+    let mut submit = |ring: &mut IoUring, slot: usize| -> io::Result<()> {
+        let offset = (rng.gen_range(0..blocks) * block_size) as u64;
+        let buffer = buffers[slot].as_mut_ptr();
+        let entry = if write {
+            opcode::Write::new(fd, buffer, block_size as u32).offset(offset).build()
+        } else {
+            opcode::Read::new(fd, buffer, block_size as u32).offset(offset).build()
+        };
+        // SAFETY: a slot's buffer is reused only after its completion has been reaped, and
+        // every submitted entry is reaped before the buffers are freed
+        unsafe { ring.submission().push(&entry.user_data(slot as u64)) }
+            .map_err(|_| io::Error::other("io_uring submission queue is full"))
+    };
+
+    // After a failure no more I/Os are submitted, but the ones in flight are still reaped
+    // before returning, as the kernel writes to their buffers until they complete
+    let mut failure = None;
+    let mut in_flight = 0;
+    for slot in 0..depth {
+        if let Err(e) = submit(&mut ring, slot) {
+            failure = Some(e);
+            break;
+        }
+        in_flight += 1;
+    }
+    let mut completed = 0;
+    while in_flight > 0 {
+        if let Err(e) = ring.submit_and_wait(1) {
+            if e.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            // The I/Os in flight can no longer be reaped; leak their buffers rather than
+            // free memory the kernel may still write to
+            std::mem::forget(buffers);
+            return Err(e);
+        }
+        let reaped: Vec<(u64, i32)> = ring.completion().map(|cqe| (cqe.user_data(), cqe.result())).collect();
+        for (slot, result) in reaped {
+            in_flight -= 1;
+            if result < 0 {
+                failure.get_or_insert(io::Error::from_raw_os_error(-result));
+                continue;
+            }
+            if (result as usize) < block_size {
+                failure.get_or_insert(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("short {}: {} of {} bytes", if write { "write" } else { "read" }, result, block_size),
+                ));
+                continue;
+            }
+            completed += 1;
+            if failure.is_none() && Instant::now() < deadline && !cancel::requested() {
+                match submit(&mut ring, slot as usize) {
+                    Ok(()) => in_flight += 1,
+                    Err(e) => failure = Some(e),
+                }
+            }
+        }
+    }
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(completed),
+    }
+}
+
+/// Runs random reads or writes with every worker keeping `depth` I/Os in flight
+fn run_queue_depth(file: &File, job: QueueDepthJob, depth: usize, write: bool) -> io::Result<Throughput> {
+    let start = Instant::now();
+    let deadline = start + job.duration;
+    let completed: u64 = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..job.workers)
+            .map(|_| {
+                scope.spawn(move || {
+                    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+                    if job.io_uring {
+                        return uring_worker(file, job, depth, write, deadline);
+                    }
+                    threaded_worker(file, job, depth, write, deadline)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap_or_else(|_| Err(worker_panicked()))).sum::<io::Result<u64>>()
+    })?;
+    if write {
+        file.sync_data()?;
+    }
+    let secs = start.elapsed().as_secs_f64();
+    Ok(Throughput {
+        mb_per_sec: (completed as usize * job.block_size) as f64 / secs / 1024.0 / 1024.0,
+        iops: completed as f64 / secs,
+    })
+}
+
+/// Fills `file` with `job.file_size` bytes, then measures random reads and writes at every
+/// queue depth. `on_step` is called with the number of depths done.
+pub fn measure_queue_depths(
+    file: &mut File,
+    job: QueueDepthJob,
+    depths: &[usize],
+    mut on_step: impl FnMut(usize),
+) -> io::Result<Vec<QueueDepthPoint>> {
+    blocks_in(job.file_size, job.block_size, "queue-depth test")?;
+    let chunk = 1 << 20;
+    let mut buffer = AlignedBuffer::new(chunk);
+    rand::thread_rng().fill(&mut buffer[..]);
+    fill(file, &buffer, job.file_size)?;
+
+    let mut points = Vec::with_capacity(depths.len());
+    for (step, &depth) in depths.iter().enumerate() {
+        points.push(QueueDepthPoint {
+            queue_depth: depth,
+            workers: job.workers,
+            random_read: run_queue_depth(file, job, depth, false)?,
+            random_write: run_queue_depth(file, job, depth, true)?,
+        });
+        // io_uring workers stop submitting on cancellation instead of failing
+        cancel::check()?;
+        on_step(step + 1);
+    }
+    Ok(points)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomTestResult {
+    pub name: String,
+    /// Share of the operations that were reads, in percent
+    pub read_pct: u32,
+    pub block_size: usize,
+    pub throughput: Throughput,
+    pub read_latency: Option<LatencySummary>,
+    pub write_latency: Option<LatencySummary>,
+}
+
+/// "Random Read", "Random Write" or "Mixed 70/30"
+pub fn random_test_name(read_pct: u32) -> String {
+    match read_pct {
+        100 => "Random Read".to_string(),
+        0 => "Random Write".to_string(),
+        pct => format!("Mixed {}/{}", pct, 100 - pct),
+    }
+}
+
+/// Issues `operations` random `block_size` I/Os over the first `file_size` bytes of `file`,
+/// each a read with a probability of `read_pct` percent and timed individually. Writes
+/// are flushed to the device before the clock stops. `on_step` is called with the number
+/// of operations done.
+pub fn run_random(
+    file: &mut File,
+    file_size: usize,
+    block_size: usize,
+    operations: usize,
+    read_pct: u32,
+    mut on_step: impl FnMut(usize),
+) -> io::Result<RandomTestResult> {
+    let blocks = blocks_in(file_size, block_size, "random test")?;
+    let mut buffer = AlignedBuffer::new(block_size);
+    let mut rng = rand::thread_rng();
+    rng.fill(&mut buffer[..]);
+    let mut read_latencies = Vec::new();
+    let mut write_latencies = Vec::new();
+
+    let start = Instant::now();
+    for i in 0..operations {
+        cancel::check()?;
+        file.seek(SeekFrom::Start((rng.gen_range(0..blocks) * block_size) as u64))?;
+        let io_start = Instant::now();
+        if rng.gen_range(0..100) < read_pct {
+            file.read_exact(&mut buffer)?;
+            read_latencies.push(io_start.elapsed());
+        } else {
+            file.write_all(&buffer)?;
+            write_latencies.push(io_start.elapsed());
+        }
+        on_step(i + 1);
+    }
+    if !write_latencies.is_empty() {
+        file.sync_data()?;
+    }
+    let secs = start.elapsed().as_secs_f64();
+
+    Ok(RandomTestResult {
+        name: random_test_name(read_pct),
+        read_pct,
+        block_size,
+        throughput: Throughput {
+            mb_per_sec: (operations * block_size) as f64 / secs / 1024.0 / 1024.0,
+            iops: operations as f64 / secs,
+        },
+        read_latency: latency::summarize(&read_latencies),
+        write_latency: latency::summarize(&write_latencies),
+    })
+}
+
+/// How each append is made durable
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SyncMethod {
+    /// `fsync`: flushes the data and all file metadata
+    Fsync,
+    /// `fdatasync`: flushes the data and only the metadata needed to read it back
+    Fdatasync,
+}
+
+impl SyncMethod {
+    pub const ALL: [SyncMethod; 2] = [SyncMethod::Fsync, SyncMethod::Fdatasync];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SyncMethod::Fsync => "fsync",
+            SyncMethod::Fdatasync => "fdatasync",
+        }
+    }
+
+    /// On Windows both flush through FlushFileBuffers
+    fn sync(self, file: &File) -> io::Result<()> {
+        match self {
+            SyncMethod::Fsync => file.sync_all(),
+            SyncMethod::Fdatasync => file.sync_data(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DurableWriteResult {
+    /// "fsync" or "fdatasync"
+    pub method: String,
+    pub append_size: usize,
+    /// Appends that were durable on the device when the clock stopped
+    pub writes: usize,
+    pub writes_per_sec: f64,
+    /// Time spent in the sync call after each append
+    pub sync_latency: Option<LatencySummary>,
+}
+
+/// Appends `append_size` bytes to a new file at `path` and makes each append durable with
+/// `method` before the next one, for `duration` or until `max_bytes` are written. This is
+/// the pattern of a database commit or a write-ahead log.
+pub fn run_durable_writes(
+    path: &Path,
+    append_size: usize,
+    duration: Duration,
+    max_bytes: usize,
+    method: SyncMethod,
+) -> io::Result<DurableWriteResult> {
+    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(path)?;
+    let mut buffer = vec![0u8; append_size];
+    rand::thread_rng().fill(&mut buffer[..]);
+    let mut sync_latencies = Vec::new();
+
+    let start = Instant::now();
+    while start.elapsed() < duration && (sync_latencies.len() + 1) * append_size <= max_bytes {
+        cancel::check()?;
+        file.write_all(&buffer)?;
+        let sync_start = Instant::now();
+        method.sync(&file)?;
+        sync_latencies.push(sync_start.elapsed());
+    }
+    let secs = start.elapsed().as_secs_f64();
+
+    Ok(DurableWriteResult {
+        method: method.name().to_string(),
+        append_size,
+        writes: sync_latencies.len(),
+        writes_per_sec: sync_latencies.len() as f64 / secs,
+        sync_latency: latency::summarize(&sync_latencies),
+    })
+}
+
+/// File-system operations timed by the metadata benchmark, in the order they run
+pub const METADATA_OPERATIONS: [&str; 8] = [
+    "File Create",
+    "File Stat",
+    "File Rename",
+    "File Delete",
+    "Dir Create",
+    "Dir Stat",
+    "Dir Rename",
+    "Dir Delete",
+];
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MetadataResult {
+    pub operation: String,
+    pub count: usize,
+    pub ops_per_sec: f64,
+}
+
+/// Runs `op` on every index and returns the operations per second
+fn time_ops(count: usize, mut op: impl FnMut(usize) -> io::Result<()>) -> io::Result<f64> {
+    let start = Instant::now();
+    for i in 0..count {
+        cancel::check()?;
+        op(i)?;
+    }
+    Ok(count as f64 / start.elapsed().as_secs_f64())
+}
+
+/// Operations per second of each of `METADATA_OPERATIONS` on `count` entries in `dir`
+fn metadata_rates(dir: &Path, count: usize, mut on_step: impl FnMut(usize)) -> io::Result<Vec<f64>> {
+    let path = |kind: &str, i: usize| dir.join(format!("{}{}", kind, i));
+    let mut rates = Vec::with_capacity(METADATA_OPERATIONS.len());
+    let mut record = |rate: f64| {
+        rates.push(rate);
+        on_step(rates.len());
+    };
+
+    record(time_ops(count, |i| File::create(path("f", i)).map(drop))?);
+    record(time_ops(count, |i| fs::metadata(path("f", i)).map(drop))?);
+    record(time_ops(count, |i| fs::rename(path("f", i), path("g", i)))?);
+    record(time_ops(count, |i| fs::remove_file(path("g", i)))?);
+    record(time_ops(count, |i| fs::create_dir(path("d", i)))?);
+    record(time_ops(count, |i| fs::metadata(path("d", i)).map(drop))?);
+    record(time_ops(count, |i| fs::rename(path("d", i), path("e", i)))?);
+    record(time_ops(count, |i| fs::remove_dir(path("e", i)))?);
+    Ok(rates)
+}
+
+/// Creates, stats, renames and deletes `count` empty files and then `count` directories
+/// inside `dir`, which must not exist yet and is removed again afterwards. `on_step` is
+/// called with the number of operation types done.
+pub fn measure_metadata(dir: &Path, count: usize, on_step: impl FnMut(usize)) -> io::Result<Vec<MetadataResult>> {
+    fs::create_dir(dir)?;
+    let rates = metadata_rates(dir, count, on_step);
+    let _ = fs::remove_dir_all(dir);
+
+    Ok(METADATA_OPERATIONS
+        .iter()
+        .zip(rates?)
+        .map(|(operation, ops_per_sec)| MetadataResult { operation: operation.to_string(), count, ops_per_sec })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_names_carry_their_owner() {
+        assert_eq!(temp_owner(&temp_name(TEST_FILE_PREFIX, 4242)), Some(TempOwner::Process(4242)));
+        assert_eq!(temp_owner(&temp_name(METADATA_DIR_PREFIX, 7)), Some(TempOwner::Process(7)));
+    }
+
+    #[test]
+    fn names_without_a_pid_are_legacy() {
+        assert_eq!(temp_owner("disk_benchmark_test.tmp"), Some(TempOwner::Legacy));
+        assert_eq!(temp_owner("disk_benchmark_metadata.tmp"), Some(TempOwner::Legacy));
+    }
+
+    #[test]
+    fn other_names_have_no_owner() {
+        assert_eq!(temp_owner("disk_benchmark_test.abc.tmp"), None);
+        assert_eq!(temp_owner("disk_benchmark_test.12.log"), None);
+        assert_eq!(temp_owner("disk_benchmark_test.log"), None);
+        assert_eq!(temp_owner("notes.12.tmp"), None);
+    }
+}
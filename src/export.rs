@@ -0,0 +1,211 @@
+// Versioned JSON export of benchmark results together with machine metadata
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sysinfo::{CpuExt, System, SystemExt};
+
+use crate::score::CompositeScore;
+use crate::stats::Summary;
+use crate::{BenchmarkResults, BenchmarkSettings, CHUNK_SIZE, DISK_CHUNK_SIZE};
+
+/// Bumped whenever a field is renamed, removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MachineInfo {
+    pub host_name: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub kernel_version: String,
+    pub cpu_brand: String,
+    pub cpu_cores: usize,
+    pub cpu_frequency_mhz: u64,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+}
+
+impl MachineInfo {
+    pub fn collect(sys: &System) -> Self {
+        let unknown = || "Unknown".to_string();
+        let cpu = sys.cpus().first();
+        Self {
+            host_name: sys.host_name().unwrap_or_else(unknown),
+            os_name: sys.name().unwrap_or_else(unknown),
+            os_version: sys.os_version().unwrap_or_else(unknown),
+            kernel_version: sys.kernel_version().unwrap_or_else(unknown),
+            cpu_brand: cpu.map(|c| c.brand().to_string()).unwrap_or_else(unknown),
+            cpu_cores: sys.cpus().len(),
+            cpu_frequency_mhz: cpu.map(|c| c.frequency()).unwrap_or(0),
+            total_memory_bytes: sys.total_memory(),
+            available_memory_bytes: sys.available_memory(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TestParameters {
+    /// Suite profile the run used, e.g. "standard"
+    #[serde(default)]
+    pub suite: String,
+    pub cpu_duration_secs: f64,
+    /// Most threads of the CPU scaling test; every logical CPU when None
+    #[serde(default)]
+    pub cpu_max_threads: Option<usize>,
+    pub memory_test_size_bytes: usize,
+    pub memory_chunk_size_bytes: usize,
+    pub memory_latency_iterations: usize,
+    #[serde(default)]
+    pub stream_array_len: usize,
+    pub disk_test_size_bytes: usize,
+    pub disk_chunk_size_bytes: usize,
+    pub disk_random_ops: usize,
+    #[serde(default)]
+    pub disk_random_block_size_bytes: usize,
+    #[serde(default)]
+    pub disk_mixed_read_pct: u32,
+    #[serde(default)]
+    pub disk_direct_io: bool,
+    #[serde(default)]
+    pub disk_sweep_file_size_bytes: usize,
+    #[serde(default)]
+    pub disk_sweep_duration_secs: f64,
+    #[serde(default)]
+    pub disk_sweep_block_sizes_bytes: Vec<usize>,
+    #[serde(default)]
+    pub disk_queue_depths: Vec<usize>,
+    #[serde(default)]
+    pub disk_qd_block_size_bytes: usize,
+    #[serde(default)]
+    pub disk_qd_duration_secs: f64,
+    #[serde(default)]
+    pub disk_workers: usize,
+    #[serde(default)]
+    pub disk_io_uring: bool,
+    #[serde(default)]
+    pub disk_fsync_append_size_bytes: usize,
+    #[serde(default)]
+    pub disk_fsync_duration_secs: f64,
+    #[serde(default)]
+    pub disk_fsync_max_bytes: usize,
+    #[serde(default)]
+    pub disk_metadata_ops: usize,
+    #[serde(default)]
+    pub high_priority: bool,
+    #[serde(default)]
+    pub cache_sweep_max_bytes: usize,
+    #[serde(default = "one")]
+    pub iterations: usize,
+    #[serde(default)]
+    pub warmup_iterations: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+impl TestParameters {
+    pub fn current(settings: &BenchmarkSettings) -> Self {
+        let profile = &settings.profile;
+        Self {
+            suite: profile.name.clone(),
+            cpu_duration_secs: profile.cpu.duration_secs,
+            cpu_max_threads: profile.cpu.max_threads,
+            memory_test_size_bytes: profile.memory_size(),
+            memory_chunk_size_bytes: CHUNK_SIZE,
+            memory_latency_iterations: profile.memory.latency_loads,
+            stream_array_len: profile.stream_array_len(),
+            disk_test_size_bytes: profile.disk_size(),
+            disk_chunk_size_bytes: DISK_CHUNK_SIZE,
+            disk_random_ops: profile.disk.random_ops,
+            disk_random_block_size_bytes: profile.disk_random_block_size(),
+            disk_mixed_read_pct: settings.mixed_read_pct,
+            disk_direct_io: settings.direct_io,
+            disk_sweep_file_size_bytes: profile.sweep_file_size(),
+            disk_sweep_duration_secs: profile.disk_sweep.duration_secs,
+            disk_sweep_block_sizes_bytes: profile.sweep_block_sizes(),
+            disk_queue_depths: settings.queue_depths.clone(),
+            disk_qd_block_size_bytes: profile.qd_block_size(),
+            disk_qd_duration_secs: profile.disk_qd.duration_secs,
+            disk_workers: settings.disk_workers,
+            disk_io_uring: settings.io_uring,
+            disk_fsync_append_size_bytes: profile.fsync_append_size(),
+            disk_fsync_duration_secs: profile.disk_fsync.duration_secs,
+            disk_fsync_max_bytes: profile.fsync_max_bytes(),
+            disk_metadata_ops: profile.disk_meta.ops,
+            high_priority: settings.high_priority,
+            cache_sweep_max_bytes: profile.cache_max_size(),
+            iterations: settings.iterations,
+            warmup_iterations: settings.warmup_iterations,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub schema_version: u32,
+    pub tool_version: String,
+    pub started_at: DateTime<Local>,
+    pub finished_at: DateTime<Local>,
+    /// Which benchmarks ran; results of the others are left at zero.
+    pub benchmarks: Vec<String>,
+    pub machine: MachineInfo,
+    pub parameters: TestParameters,
+    pub results: BenchmarkResults,
+    pub units: BTreeMap<String, String>,
+    /// Per-metric summaries, present when the run had more than one iteration
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub statistics: BTreeMap<String, Summary>,
+    /// Composite score against the reference machine, present when a weighted metric ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<CompositeScore>,
+}
+
+impl BenchmarkReport {
+    pub fn new(
+        sys: &System,
+        settings: &BenchmarkSettings,
+        benchmarks: &[&str],
+        results: &BenchmarkResults,
+        started_at: DateTime<Local>,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at,
+            finished_at: Local::now(),
+            benchmarks: benchmarks.iter().map(|b| b.to_string()).collect(),
+            machine: MachineInfo::collect(sys),
+            parameters: TestParameters::current(settings),
+            results: results.clone(),
+            units: results
+                .metrics()
+                .iter()
+                .map(|m| (m.key.to_string(), m.unit.to_string()))
+                .collect(),
+            statistics: BTreeMap::new(),
+            score: CompositeScore::compute(results, benchmarks, &settings.profile.score.weights),
+        }
+    }
+
+    /// Whether `benchmark` ran; the results of those that did not are zero, not measured
+    pub fn ran(&self, benchmark: &str) -> bool {
+        self.benchmarks.iter().any(|b| b == benchmark)
+    }
+
+    /// File name used when exporting from the menu, e.g. `benchmark_20250318_120000.json`
+    pub fn default_file_name(&self) -> PathBuf {
+        PathBuf::from(format!("benchmark_{}.json", self.started_at.format("%Y%m%d_%H%M%S")))
+    }
+
+    pub fn write_json(&self, path: &Path) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+}
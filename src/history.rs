@@ -0,0 +1,268 @@
+// On-disk history of full benchmark runs, kept in one file per machine
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use eonflux_benchmark::export::BenchmarkReport;
+use eonflux_benchmark::BenchmarkResults;
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Directory holding the history and other saved data.
+/// `EONFLUX_BENCHMARK_HOME` overrides the platform default.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("EONFLUX_BENCHMARK_HOME") {
+        return PathBuf::from(dir);
+    }
+    if cfg!(windows) {
+        if let Some(appdata) = std::env::var_os("APPDATA") {
+            return PathBuf::from(appdata).join("EonfluxTech Benchmark");
+        }
+    }
+    if let Some(data_home) = std::env::var_os("XDG_DATA_HOME") {
+        return PathBuf::from(data_home).join("eonflux_benchmark");
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        return PathBuf::from(home).join(".local/share/eonflux_benchmark");
+    }
+    PathBuf::from(".eonflux_benchmark")
+}
+
+/// Makes a host name safe to use as a file name
+pub fn file_stem(host_name: &str) -> String {
+    host_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub report: BenchmarkReport,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct History {
+    #[serde(skip)]
+    path: PathBuf,
+    next_id: u32,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Loads the history of `host_name`, starting an empty one if none exists yet
+    pub fn open(host_name: &str) -> io::Result<Self> {
+        let path = data_dir()
+            .join("history")
+            .join(format!("{}.json", file_stem(host_name)));
+
+        let mut history = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => History {
+                path: PathBuf::new(),
+                next_id: 1,
+                entries: Vec::new(),
+            },
+            Err(e) => return Err(e),
+        };
+        history.path = path;
+        Ok(history)
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn add(&mut self, report: BenchmarkReport) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(HistoryEntry {
+            id,
+            tags: Vec::new(),
+            report,
+        });
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != before
+    }
+
+    pub fn tag(&mut self, id: u32, tag: &str) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                if !entry.tags.iter().any(|t| t == tag) {
+                    entry.tags.push(tag.to_string());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn untag(&mut self, id: u32, tag: &str) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                entry.tags.retain(|t| t != tag);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Writes the history through a temporary file so an interrupted save keeps the old copy
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+pub fn print_runs(history: &History, selected: Option<usize>) {
+    if history.entries.is_empty() {
+        println!("No runs recorded yet. Run the full system benchmark to add one.");
+        return;
+    }
+
+    println!(
+        "  {:>4}  {:<19}  {:>12}  {:>10}  {:>10}  {:>10}  Tags",
+        "ID", "Date", "CPU ops/s", "Mem MB/s", "Disk MB/s", "4K IOPS"
+    );
+    for (i, entry) in history.entries.iter().enumerate() {
+        let r = &entry.report.results;
+        // Benchmarks the run did not include have no value rather than zero
+        let value = |benchmark: &str, value: f64| {
+            if entry.report.ran(benchmark) { format!("{:.0}", value) } else { "-".to_string() }
+        };
+        let line = format!(
+            "{:>4}  {:<19}  {:>12}  {:>10}  {:>10}  {:>10}  {}",
+            entry.id,
+            entry.report.started_at.format("%Y-%m-%d %H:%M:%S"),
+            value("cpu", r.cpu_score),
+            value("memory", (r.memory_read_speed + r.memory_write_speed) / 2.0),
+            value("disk", (r.disk_read_speed + r.disk_write_speed) / 2.0),
+            value("disk", r.disk_random_read_iops),
+            entry.tags.join(", "),
+        );
+        if selected == Some(i) {
+            println!("{} {}", "►".bright_green(), line.bright_white());
+        } else {
+            println!("  {}", line);
+        }
+    }
+}
+
+fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            if max > min {
+                let level = ((v - min) / (max - min) * (SPARK_LEVELS.len() - 1) as f64).round();
+                SPARK_LEVELS[level as usize]
+            } else {
+                SPARK_LEVELS[SPARK_LEVELS.len() / 2]
+            }
+        })
+        .collect()
+}
+
+/// Values of the metric at `index` of `BenchmarkResults::metrics`, oldest first, from the
+/// runs that included its benchmark; optional tests such as direct I/O are in some only
+fn series(entries: &[HistoryEntry], index: usize) -> Vec<f64> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let metric = &entry.report.results.metrics()[index];
+            entry.report.ran(metric.benchmark).then_some(metric.value)
+        })
+        .collect()
+}
+
+pub fn print_trends(history: &History) {
+    if history.entries.len() < 2 {
+        println!("At least two recorded runs are needed to show trends.");
+        return;
+    }
+
+    let first = history.entries.first().unwrap();
+    let last = history.entries.last().unwrap();
+    println!(
+        "{} runs from {} to {}",
+        history.entries.len(),
+        first.report.started_at.format("%Y-%m-%d"),
+        last.report.started_at.format("%Y-%m-%d")
+    );
+    println!();
+    println!(
+        "{:<16} {:>12} {:>12} {:>12} {:>12} {:>9}  Trend",
+        "Metric", "First", "Latest", "Min", "Max", "Change"
+    );
+
+    for (i, metric) in BenchmarkResults::default().metrics().iter().enumerate() {
+        let values = series(&history.entries, i);
+        if values.is_empty() {
+            continue;
+        }
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let (first_value, last_value) = (values[0], values[values.len() - 1]);
+        let change = if first_value != 0.0 {
+            format!("{:+.1}%", (last_value - first_value) / first_value * 100.0)
+        } else {
+            "n/a".to_string()
+        };
+        println!(
+            "{:<16} {:>12.2} {:>12.2} {:>12.2} {:>12.2} {:>9}  {} {}",
+            metric.label,
+            first_value,
+            last_value,
+            min,
+            max,
+            change,
+            sparkline(&values).bright_cyan(),
+            metric.unit
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_report;
+
+    fn entry(id: u32, report: BenchmarkReport) -> HistoryEntry {
+        HistoryEntry { id, tags: Vec::new(), report }
+    }
+
+    fn index_of(key: &str) -> usize {
+        BenchmarkResults::default().metrics().iter().position(|m| m.key == key).unwrap()
+    }
+
+    #[test]
+    fn trends_only_include_runs_of_the_metric_benchmark() {
+        let entries = [
+            entry(1, test_report(&["disk", "disk-direct"], &[("disk_read_speed", 500.0), ("disk_direct_read_speed", 400.0)])),
+            entry(2, test_report(&["disk"], &[("disk_read_speed", 550.0)])),
+            entry(3, test_report(&["cpu"], &[("cpu_score", 20_000.0)])),
+            entry(4, test_report(&["disk", "disk-direct"], &[("disk_read_speed", 600.0), ("disk_direct_read_speed", 450.0)])),
+        ];
+        assert_eq!(series(&entries, index_of("disk_read_speed")), [500.0, 550.0, 600.0]);
+        assert_eq!(series(&entries, index_of("disk_direct_read_speed")), [400.0, 450.0]);
+        assert_eq!(series(&entries, index_of("cpu_score")), [20_000.0]);
+        assert!(series(&entries, index_of("memory_read_speed")).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+// Per-operation latency percentiles and a log-scale histogram
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    /// Operations that took at most this long and more than the previous bucket's bound
+    pub upper_us: f64,
+    pub count: usize,
+}
+
+/// Latencies of individual operations, in microseconds
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub min_us: f64,
+    pub avg_us: f64,
+    pub p50_us: f64,
+    pub p90_us: f64,
+    pub p99_us: f64,
+    pub p999_us: f64,
+    pub max_us: f64,
+    /// Buckets on a 1-2-5 scale from the fastest to the slowest operation
+    pub histogram: Vec<HistogramBucket>,
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    // Multiplying first keeps e.g. 99.9% of 1000 at exactly 999
+    let rank = (p * sorted.len() as f64 / 100.0).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// The bound after `bound` on the 1-2-5 scale: 1, 2, 5, 10, 20, 50 …
+fn next_bound(bound: f64) -> f64 {
+    let mantissa = bound / 10f64.powf(bound.log10().floor());
+    if mantissa > 1.5 && mantissa < 3.5 {
+        bound * 2.5
+    } else {
+        bound * 2.0
+    }
+}
+
+/// Summarizes operation latencies; None when there are none
+pub fn summarize(latencies: &[Duration]) -> Option<LatencySummary> {
+    if latencies.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<f64> = latencies.iter().map(|d| d.as_secs_f64() * 1e6).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+
+    let mut histogram = Vec::new();
+    let mut upper = 1.0;
+    while upper < min {
+        upper = next_bound(upper);
+    }
+    let mut counted = 0;
+    while counted < sorted.len() {
+        let count = sorted[counted..].iter().take_while(|&&v| v <= upper).count();
+        histogram.push(HistogramBucket { upper_us: upper, count });
+        counted += count;
+        upper = next_bound(upper);
+    }
+
+    Some(LatencySummary {
+        samples: sorted.len(),
+        min_us: min,
+        avg_us: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50_us: percentile(&sorted, 50.0),
+        p90_us: percentile(&sorted, 90.0),
+        p99_us: percentile(&sorted, 99.0),
+        p999_us: percentile(&sorted, 99.9),
+        max_us: max,
+        histogram,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_close;
+
+    fn micros(values: impl IntoIterator<Item = u64>) -> Vec<Duration> {
+        values.into_iter().map(Duration::from_micros).collect()
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let ten: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_eq!(percentile(&ten, 50.0), 5.0);
+        assert_eq!(percentile(&ten, 90.0), 9.0);
+        assert_eq!(percentile(&ten, 99.0), 10.0);
+        assert_eq!(percentile(&ten, 99.9), 10.0);
+
+        let thousand: Vec<f64> = (1..=1000).map(f64::from).collect();
+        assert_eq!(percentile(&thousand, 50.0), 500.0);
+        assert_eq!(percentile(&thousand, 99.0), 990.0);
+        assert_eq!(percentile(&thousand, 99.9), 999.0);
+
+        assert_eq!(percentile(&[7.0], 0.0), 7.0);
+    }
+
+    #[test]
+    fn bounds_follow_the_1_2_5_scale() {
+        let mut bounds = vec![1.0];
+        for _ in 0..6 {
+            bounds.push(next_bound(*bounds.last().unwrap()));
+        }
+        for (bound, expected) in bounds.iter().zip([1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0]) {
+            assert_close(*bound, expected);
+        }
+    }
+
+    #[test]
+    fn summarizes_the_spread() {
+        let summary = summarize(&micros(1..=100)).unwrap();
+        assert_eq!(summary.samples, 100);
+        assert_close(summary.min_us, 1.0);
+        assert_close(summary.avg_us, 50.5);
+        assert_close(summary.p50_us, 50.0);
+        assert_close(summary.p99_us, 99.0);
+        assert_close(summary.max_us, 100.0);
+    }
+
+    #[test]
+    fn histogram_buckets_count_every_sample_once() {
+        let summary = summarize(&micros([3, 3, 4, 8, 15, 40, 900, 2_500])).unwrap();
+        assert_eq!(summary.histogram.iter().map(|b| b.count).sum::<usize>(), summary.samples);
+        // The first bucket is the one holding the fastest operation, the last the slowest
+        let bounds: Vec<f64> = summary.histogram.iter().map(|b| b.upper_us).collect();
+        assert_eq!(bounds, [5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0]);
+        let counts: Vec<usize> = summary.histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [3, 1, 1, 1, 0, 0, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn no_operations_have_no_summary() {
+        assert!(summarize(&[]).is_none());
+    }
+}
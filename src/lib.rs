@@ -0,0 +1,270 @@
+// Credits to https://github.com/EonfluxTech/EonfluxTech-Benchmark-Tool contributed by @Dyplay
+// 2025-03-18 12:00 AM copyright EonfluxTech.com 2025
+
+// The benchmarks as a library: each one implements `benchmark::Benchmark` and fills in
+// `BenchmarkResults` without printing, so the menu, the command line and other Rust tools
+// can run them alike
+
+pub mod benchmark;
+pub mod cancel;
+pub mod cleanup;
+pub mod cpu;
+pub mod disk;
+pub mod export;
+pub mod latency;
+pub mod memory;
+pub mod platform;
+pub mod preflight;
+pub mod profile;
+pub mod score;
+pub mod stats;
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::profile::Profile;
+
+// Sizes, durations and counts of the tests come from the suite profile (see `profile`);
+// these are the fixed parts and the floors tests scale down to when space is short
+pub const MEMORY_MIN_TEST_SIZE: usize = 1024 * 1024 * 32; // smallest scaled-down size
+pub const CHUNK_SIZE: usize = 1024 * 1024; // 1 MB
+pub const DISK_MIN_TEST_SIZE: usize = 1024 * 1024 * 64; // smallest scaled-down size
+pub const DISK_CHUNK_SIZE: usize = 1024 * 1024; // 1 MB chunks for disk operations
+pub const STREAM_MIN_ARRAY_LEN: usize = 1024 * 1024; // 8 MB per f64 array
+pub const DEFAULT_MIXED_READ_PCT: u32 = 70;
+pub const DEFAULT_QUEUE_DEPTHS: [usize; 3] = [1, 4, 32];
+
+/// How each benchmark is repeated and where the disk benchmark writes
+#[derive(Debug, Clone)]
+pub struct BenchmarkSettings {
+    /// Measured runs; with more than one the report holds statistical summaries
+    pub iterations: usize,
+    /// Runs before the measured ones whose results are discarded
+    pub warmup_iterations: usize,
+    /// Directory for the disk test file; the current directory when None
+    pub disk_dir: Option<PathBuf>,
+    /// Also run the sequential disk tests with the OS page cache bypassed
+    pub direct_io: bool,
+    /// Share of reads in the mixed random disk test, in percent
+    pub mixed_read_pct: u32,
+    /// Outstanding I/Os per worker in the queue-depth test
+    pub queue_depths: Vec<usize>,
+    /// Concurrent workers in the queue-depth test
+    pub disk_workers: usize,
+    /// Submit queue-depth test I/O through io_uring instead of one thread per I/O
+    pub io_uring: bool,
+    /// Run at a raised process priority
+    pub high_priority: bool,
+    /// Tests of the full benchmark and the sizes and durations every test runs with
+    pub profile: Profile,
+}
+
+impl Default for BenchmarkSettings {
+    fn default() -> Self {
+        Self {
+            iterations: 1,
+            warmup_iterations: 0,
+            disk_dir: None,
+            direct_io: false,
+            mixed_read_pct: DEFAULT_MIXED_READ_PCT,
+            queue_depths: DEFAULT_QUEUE_DEPTHS.to_vec(),
+            disk_workers: 1,
+            io_uring: false,
+            high_priority: false,
+            profile: Profile::standard(),
+        }
+    }
+}
+
+impl BenchmarkSettings {
+    /// Switches to `profile`, taking over the run settings it sets; options given on the
+    /// command line afterwards take precedence
+    pub fn apply_profile(&mut self, profile: Profile) {
+        if let Some(iterations) = profile.iterations {
+            self.iterations = iterations;
+        }
+        if let Some(warmup_iterations) = profile.warmup_iterations {
+            self.warmup_iterations = warmup_iterations;
+        }
+        if let Some(dir) = &profile.disk.dir {
+            self.disk_dir = Some(dir.clone());
+        }
+        if let Some(direct_io) = profile.disk.direct_io {
+            self.direct_io = direct_io;
+        }
+        if let Some(pct) = profile.disk.mixed_read_pct {
+            self.mixed_read_pct = pct;
+        }
+        if let Some(depths) = &profile.disk_qd.queue_depths {
+            self.queue_depths = depths.clone();
+        }
+        if let Some(workers) = profile.disk_qd.workers {
+            self.disk_workers = workers;
+        }
+        self.profile = profile;
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchmarkResults {
+    /// Single-thread operations per second
+    pub cpu_score: f64,
+    /// Operations per second with one thread per logical CPU
+    pub cpu_multi_score: f64,
+    pub memory_read_speed: f64,
+    pub memory_write_speed: f64,
+    pub memory_latency: f64,
+    /// STREAM kernel bandwidths with one thread per logical CPU, in GB/s
+    pub stream_copy: f64,
+    pub stream_scale: f64,
+    pub stream_add: f64,
+    pub stream_triad: f64,
+    pub disk_read_speed: f64,
+    pub disk_write_speed: f64,
+    /// 4 KB random operations per second
+    pub disk_random_read_iops: f64,
+    pub disk_random_write_iops: f64,
+    pub disk_mixed_iops: f64,
+    /// Sequential speeds with the page cache bypassed, in MB/s
+    pub disk_direct_read_speed: f64,
+    pub disk_direct_write_speed: f64,
+    /// 99th percentile latency of the 4 KB random reads and writes, in µs
+    pub disk_read_p99: f64,
+    pub disk_write_p99: f64,
+    /// Small appends made durable per second, and the p99 latency of the sync call in µs
+    pub disk_fsync_writes_per_sec: f64,
+    pub disk_fsync_p99: f64,
+    pub disk_fdatasync_writes_per_sec: f64,
+    pub disk_fdatasync_p99: f64,
+    /// File-system metadata operations per second
+    pub disk_meta_file_create: f64,
+    pub disk_meta_file_stat: f64,
+    pub disk_meta_file_rename: f64,
+    pub disk_meta_file_delete: f64,
+    pub disk_meta_dir_create: f64,
+    pub disk_meta_dir_stat: f64,
+    pub disk_meta_dir_rename: f64,
+    pub disk_meta_dir_delete: f64,
+    pub cpu_scaling: Vec<cpu::CpuScalingPoint>,
+    pub stream: Vec<memory::StreamResult>,
+    pub cache_sweep: Vec<memory::CacheSweepPoint>,
+    pub cache_levels: Vec<memory::CacheLevel>,
+    pub disk_target: Option<disk::DiskTarget>,
+    pub disk_sequential_read_latency: Option<latency::LatencySummary>,
+    pub disk_sequential_write_latency: Option<latency::LatencySummary>,
+    pub disk_random: Vec<disk::RandomTestResult>,
+    pub disk_block_sizes: Vec<disk::BlockSizePoint>,
+    pub disk_queue_depths: Vec<disk::QueueDepthPoint>,
+    pub disk_durable_writes: Vec<disk::DurableWriteResult>,
+    pub disk_metadata: Vec<disk::MetadataResult>,
+    /// Tests that ran with less data than configured because memory or disk space was short
+    pub preflight_notes: Vec<String>,
+}
+
+pub struct Metric {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub unit: &'static str,
+    /// Benchmark that produces this metric, as listed in `BenchmarkReport::benchmarks`
+    pub benchmark: &'static str,
+    pub higher_is_better: bool,
+    pub value: f64,
+}
+
+impl Metric {
+    const fn new(key: &'static str, label: &'static str, unit: &'static str, benchmark: &'static str, higher_is_better: bool, value: f64) -> Self {
+        Self { key, label, unit, benchmark, higher_is_better, value }
+    }
+}
+
+impl BenchmarkResults {
+    /// Every field with its display label and unit, in report order
+    pub fn metrics(&self) -> [Metric; 30] {
+        [
+            Metric::new("cpu_score", "CPU Score", "ops/s", "cpu", true, self.cpu_score),
+            Metric::new("cpu_multi_score", "CPU All-Core", "ops/s", "cpu", true, self.cpu_multi_score),
+            Metric::new("memory_read_speed", "Memory Read", "MB/s", "memory", true, self.memory_read_speed),
+            Metric::new("memory_write_speed", "Memory Write", "MB/s", "memory", true, self.memory_write_speed),
+            Metric::new("memory_latency", "Memory Latency", "ns", "memory", false, self.memory_latency),
+            Metric::new("stream_copy", "STREAM Copy", "GB/s", "memory", true, self.stream_copy),
+            Metric::new("stream_scale", "STREAM Scale", "GB/s", "memory", true, self.stream_scale),
+            Metric::new("stream_add", "STREAM Add", "GB/s", "memory", true, self.stream_add),
+            Metric::new("stream_triad", "STREAM Triad", "GB/s", "memory", true, self.stream_triad),
+            Metric::new("disk_read_speed", "Disk Read", "MB/s", "disk", true, self.disk_read_speed),
+            Metric::new("disk_write_speed", "Disk Write", "MB/s", "disk", true, self.disk_write_speed),
+            Metric::new("disk_random_read_iops", "Disk 4K Read", "IOPS", "disk", true, self.disk_random_read_iops),
+            Metric::new("disk_random_write_iops", "Disk 4K Write", "IOPS", "disk", true, self.disk_random_write_iops),
+            Metric::new("disk_mixed_iops", "Disk 4K Mixed", "IOPS", "disk", true, self.disk_mixed_iops),
+            Metric::new("disk_read_p99", "Disk 4K Read p99", "µs", "disk", false, self.disk_read_p99),
+            Metric::new("disk_write_p99", "Disk 4K Write p99", "µs", "disk", false, self.disk_write_p99),
+            Metric::new("disk_direct_read_speed", "Disk Read Direct", "MB/s", "disk-direct", true, self.disk_direct_read_speed),
+            Metric::new("disk_direct_write_speed", "Disk Write Direct", "MB/s", "disk-direct", true, self.disk_direct_write_speed),
+            Metric::new("disk_fsync_writes_per_sec", "Durable Writes fsync", "writes/s", "disk-fsync", true, self.disk_fsync_writes_per_sec),
+            Metric::new("disk_fsync_p99", "fsync p99", "µs", "disk-fsync", false, self.disk_fsync_p99),
+            Metric::new("disk_fdatasync_writes_per_sec", "Durable Writes fdatasync", "writes/s", "disk-fsync", true, self.disk_fdatasync_writes_per_sec),
+            Metric::new("disk_fdatasync_p99", "fdatasync p99", "µs", "disk-fsync", false, self.disk_fdatasync_p99),
+            Metric::new("disk_meta_file_create", "File Create", "ops/s", "disk-meta", true, self.disk_meta_file_create),
+            Metric::new("disk_meta_file_stat", "File Stat", "ops/s", "disk-meta", true, self.disk_meta_file_stat),
+            Metric::new("disk_meta_file_rename", "File Rename", "ops/s", "disk-meta", true, self.disk_meta_file_rename),
+            Metric::new("disk_meta_file_delete", "File Delete", "ops/s", "disk-meta", true, self.disk_meta_file_delete),
+            Metric::new("disk_meta_dir_create", "Dir Create", "ops/s", "disk-meta", true, self.disk_meta_dir_create),
+            Metric::new("disk_meta_dir_stat", "Dir Stat", "ops/s", "disk-meta", true, self.disk_meta_dir_stat),
+            Metric::new("disk_meta_dir_rename", "Dir Rename", "ops/s", "disk-meta", true, self.disk_meta_dir_rename),
+            Metric::new("disk_meta_dir_delete", "Dir Delete", "ops/s", "disk-meta", true, self.disk_meta_dir_delete),
+        ]
+    }
+
+    /// Results with the given metrics set and everything else left at zero
+    pub fn from_metrics(metrics: &[(&str, f64)]) -> Self {
+        let mut results = Self::default();
+        for &(key, value) in metrics {
+            results.set_metric(key, value);
+        }
+        results
+    }
+
+    pub fn set_metric(&mut self, key: &str, value: f64) {
+        let field = match key {
+            "cpu_score" => &mut self.cpu_score,
+            "cpu_multi_score" => &mut self.cpu_multi_score,
+            "memory_read_speed" => &mut self.memory_read_speed,
+            "memory_write_speed" => &mut self.memory_write_speed,
+            "memory_latency" => &mut self.memory_latency,
+            "stream_copy" => &mut self.stream_copy,
+            "stream_scale" => &mut self.stream_scale,
+            "stream_add" => &mut self.stream_add,
+            "stream_triad" => &mut self.stream_triad,
+            "disk_read_speed" => &mut self.disk_read_speed,
+            "disk_write_speed" => &mut self.disk_write_speed,
+            "disk_random_read_iops" => &mut self.disk_random_read_iops,
+            "disk_random_write_iops" => &mut self.disk_random_write_iops,
+            "disk_mixed_iops" => &mut self.disk_mixed_iops,
+            "disk_read_p99" => &mut self.disk_read_p99,
+            "disk_write_p99" => &mut self.disk_write_p99,
+            "disk_direct_read_speed" => &mut self.disk_direct_read_speed,
+            "disk_direct_write_speed" => &mut self.disk_direct_write_speed,
+            "disk_fsync_writes_per_sec" => &mut self.disk_fsync_writes_per_sec,
+            "disk_fsync_p99" => &mut self.disk_fsync_p99,
+            "disk_fdatasync_writes_per_sec" => &mut self.disk_fdatasync_writes_per_sec,
+            "disk_fdatasync_p99" => &mut self.disk_fdatasync_p99,
+            "disk_meta_file_create" => &mut self.disk_meta_file_create,
+            "disk_meta_file_stat" => &mut self.disk_meta_file_stat,
+            "disk_meta_file_rename" => &mut self.disk_meta_file_rename,
+            "disk_meta_file_delete" => &mut self.disk_meta_file_delete,
+            "disk_meta_dir_create" => &mut self.disk_meta_dir_create,
+            "disk_meta_dir_stat" => &mut self.disk_meta_dir_stat,
+            "disk_meta_dir_rename" => &mut self.disk_meta_dir_rename,
+            "disk_meta_dir_delete" => &mut self.disk_meta_dir_delete,
+            _ => return,
+        };
+        *field = value;
+    }
+}
+
+/// Asserts that two computed values agree up to rounding
+#[cfg(test)]
+pub(crate) fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() <= 1e-9 * expected.abs().max(1.0), "{} is not {}", actual, expected);
+}
@@ -1,72 +1,268 @@
 // Credits to https://github.com/EonfluxTech/EonfluxTech-Benchmark-Tool contributed by @Dyplay
 // 2025-03-18 12:00 AM copyright EonfluxTech.com 2025
 
+mod baseline;
+mod cli;
+mod history;
+mod ui;
+
+use cli::{BaselineAction, Cli, Command, DiskTest, HistoryAction};
 use colored::*;
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode},
+    event::{self, Event, KeyCode, KeyModifiers},
     execute,
-    terminal::{self, ClearType, EnableLineWrap},
+    terminal::{self, ClearType},
 };
 use sysinfo::{CpuExt, System, SystemExt, DiskExt};
-use std::io::{stdout, Write, Read, Seek, SeekFrom};
+use std::cell::RefCell;
+use std::io::{stdout, Write};
 use std::time::{Duration, Instant};
-use std::fs::OpenOptions;
-use indicatif::{ProgressBar, ProgressStyle, MultiProgress};
-use rand::Rng;
+use std::path::PathBuf;
+use indicatif::{ProgressBar, ProgressStyle};
 use chrono::Local;
+use eonflux_benchmark::benchmark::{
+    self, Benchmark, BlockSizeSweep, CacheSweep, Context, CpuBenchmark, DiskBenchmark, DurableWriteBenchmark, MemoryBenchmark,
+    MetadataBenchmark, Progress, QueueDepthBenchmark, Suite,
+};
+use eonflux_benchmark::export::BenchmarkReport;
+use eonflux_benchmark::{cancel, cleanup, disk, latency, memory, platform, stats, BenchmarkResults, BenchmarkSettings};
+use eonflux_benchmark::profile::{self, Profile};
+use eonflux_benchmark::score::{self, CompositeScore};
+use eonflux_benchmark::DISK_CHUNK_SIZE;
+use history::History;
+
+/// How often the menu checks for a cancel key while a benchmark runs
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shows each step of a benchmark as a heading with a progress bar below it
+struct BarProgress {
+    bar: RefCell<Option<ProgressBar>>,
+}
+
+impl BarProgress {
+    fn new() -> Self {
+        Self { bar: RefCell::new(None) }
+    }
+
+    /// Completes the bar of the last step
+    fn finish(&self) {
+        if let Some(pb) = self.bar.borrow_mut().take() {
+            pb.finish();
+        }
+    }
+}
+
+impl Progress for BarProgress {
+    fn start(&self, step: &str, len: u64) {
+        if self.bar.borrow().is_some() {
+            self.finish();
+            println!();
+        }
+        println!("{}", format!("{}...", step).bright_yellow());
+        let pb = ProgressBar::new(len);
+        pb.set_style(ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
+            .unwrap()
+            .progress_chars("#>-"));
+        *self.bar.borrow_mut() = Some(pb);
+    }
+
+    fn set_position(&self, position: u64) {
+        if let Some(pb) = self.bar.borrow().as_ref() {
+            pb.set_position(position);
+        }
+    }
+
+    fn set_message(&self, message: &str) {
+        if let Some(pb) = self.bar.borrow().as_ref() {
+            pb.set_message(message.to_string());
+        }
+    }
 
-const MEMORY_TEST_SIZE: usize = 1024 * 1024 * 256; // 256 MB
-const CHUNK_SIZE: usize = 1024 * 1024; // 1 MB
-const DISK_TEST_SIZE: usize = 1024 * 1024 * 512; // 512 MB
-const DISK_CHUNK_SIZE: usize = 1024 * 1024; // 1 MB chunks for disk operations
-
-#[derive(Default)]
-struct BenchmarkResults {
-    cpu_score: f64,
-    memory_read_speed: f64,
-    memory_write_speed: f64,
-    memory_latency: f64,
-    disk_read_speed: f64,
-    disk_write_speed: f64,
-    disk_iops: f64,
+    fn note(&self, note: &str) {
+        println!("{}", format!("► {}", note).bright_yellow());
+    }
+
+    fn benchmark(&self, title: &str) {
+        if self.bar.borrow().is_some() {
+            self.finish();
+            println!();
+        }
+        println!("{}", format!("Running {}...", title).bright_yellow());
+    }
 }
 
 struct BenchmarkTool {
     sys: System,
+    interactive: bool,
+    settings: BenchmarkSettings,
+    /// Options from the command line, kept for when the menu switches profiles
+    overrides: cli::Overrides,
 }
 
 impl BenchmarkTool {
-    fn new() -> Self {
+    fn new(interactive: bool, cli: &Cli) -> Self {
         Self {
             sys: System::new_all(),
+            interactive,
+            settings: cli.settings.clone(),
+            overrides: cli.overrides.clone(),
         }
     }
 
+    /// Measures like `measure_runs`; in the menu the runs happen on a worker thread while
+    /// this thread watches the keyboard, and Esc or 'B' cancels them
+    fn measure<F>(&mut self, benchmarks: &[&str], pass: F) -> std::io::Result<BenchmarkReport>
+    where
+        F: FnMut(&mut Self, &mut BenchmarkResults) -> std::io::Result<()> + Send,
+    {
+        cancel::reset();
+        if !self.interactive {
+            return self.measure_runs(benchmarks, pass);
+        }
+
+        println!("{}", "Press 'B' or ESC to cancel".bright_black());
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(|| self.measure_runs(benchmarks, pass));
+            while !worker.is_finished() {
+                if !event::poll(INPUT_POLL_INTERVAL).unwrap_or(false) {
+                    continue;
+                }
+                if let Ok(Event::Key(key_event)) = event::read() {
+                    let ctrl_c = key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL);
+                    if ctrl_c || matches!(key_event.code, KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc) {
+                        cancel::request();
+                    }
+                }
+            }
+            worker
+                .join()
+                .unwrap_or_else(|_| Err(std::io::Error::other("the benchmark panicked")))
+        })
+    }
+
+    /// Runs `pass` for the configured warm-up and measured iterations and builds a report.
+    /// With more than one iteration the results are the outlier-filtered means.
+    fn measure_runs<F>(&mut self, benchmarks: &[&str], mut pass: F) -> std::io::Result<BenchmarkReport>
+    where
+        F: FnMut(&mut Self, &mut BenchmarkResults) -> std::io::Result<()>,
+    {
+        let started_at = Local::now();
+        let BenchmarkSettings { iterations, warmup_iterations, .. } = self.settings;
+
+        for i in 1..=warmup_iterations {
+            println!("{}", format!("Warm-up run {}/{} (discarded)", i, warmup_iterations).bright_black());
+            pass(self, &mut BenchmarkResults::default())?;
+        }
+
+        let mut runs = Vec::with_capacity(iterations);
+        for i in 1..=iterations {
+            if iterations > 1 {
+                println!("{}", format!("Iteration {}/{}", i, iterations).bright_cyan());
+            }
+            let mut results = BenchmarkResults::default();
+            pass(self, &mut results)?;
+            runs.push(results);
+        }
+
+        if runs.len() == 1 {
+            return Ok(BenchmarkReport::new(&self.sys, &self.settings, benchmarks, &runs[0], started_at));
+        }
+        let (results, summaries) = stats::aggregate(&runs, benchmarks);
+        let mut report = BenchmarkReport::new(&self.sys, &self.settings, benchmarks, &results, started_at);
+        report.statistics = summaries;
+        Ok(report)
+    }
+
     fn display_back_button(&self) {
         println!("\n{}", "═══════════════════════════════════════".bright_blue());
-        println!("{} {}", "◄".bright_red(), "Press 'B' or ESC to go back to main menu");
+        println!("{} Press 'B' or ESC to go back to main menu", "◄".bright_red());
     }
 
-    fn wait_for_back(&self) -> bool {
-        if let Ok(Event::Key(key_event)) = event::read() {
-            matches!(key_event.code, KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc)
-        } else {
-            false
+    /// Shows the results of a run, or why it failed, and the actions available for them
+    fn show_outcome(&self, outcome: &std::io::Result<BenchmarkReport>) {
+        match outcome {
+            Ok(report) if !report.statistics.is_empty() => ui::stats::print_summaries(&report.results, &report.statistics),
+            Ok(_) => {}
+            Err(e) => println!("\n{} {}", "Benchmark failed:".bright_red(), e),
+        }
+
+        self.display_back_button();
+        println!("\nPress 'R' to run the benchmark again");
+        if outcome.is_ok() {
+            println!("Press 'E' to export the results as JSON, 'S' to save them as a baseline or 'C' to compare with one");
+        }
+    }
+
+    /// Waits on a results screen; returns true to run the benchmark again, false to go back.
+    fn wait_on_results(&self, outcome: &std::io::Result<BenchmarkReport>) -> bool {
+        // A cancelled run goes straight back to the menu
+        if matches!(outcome, Err(e) if cancel::is_cancellation(e)) {
+            return false;
+        }
+        loop {
+            if let Ok(Event::Key(key_event)) = event::read() {
+                let report = match (key_event.code, outcome) {
+                    (KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc, _) => return false,
+                    (KeyCode::Char('r') | KeyCode::Char('R'), _) => return true,
+                    (_, Ok(report)) => report,
+                    (_, Err(_)) => continue,
+                };
+                match key_event.code {
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        let path = report.default_file_name();
+                        match report.write_json(&path) {
+                            Ok(()) => println!("{} {}", "► Results exported to".bright_green(), path.display()),
+                            Err(e) => println!("{} {}", "► Export failed:".bright_red(), e),
+                        }
+                    }
+                    KeyCode::Char('s') | KeyCode::Char('S') => {
+                        let Some(name) = self.read_line("Baseline name:") else { continue };
+                        if name.trim().is_empty() {
+                            continue;
+                        }
+                        match baseline::save(name.trim(), report) {
+                            Ok(path) => println!("\n{} {}", "► Baseline saved to".bright_green(), path.display()),
+                            Err(e) => println!("\n{} {}", "► Could not save the baseline:".bright_red(), e),
+                        }
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C') => {
+                        let Some(name) = self.read_line("Compare with baseline:") else { continue };
+                        if name.trim().is_empty() {
+                            continue;
+                        }
+                        println!("\n");
+                        match baseline::load(name.trim()) {
+                            Ok(saved) => {
+                                let comparisons = baseline::compare(&saved, report, baseline::DEFAULT_TOLERANCE_PCT);
+                                baseline::print_comparison(name.trim(), &saved, &comparisons, baseline::DEFAULT_TOLERANCE_PCT);
+                            }
+                            Err(e) => println!("{} {}", "► Could not load the baseline:".bright_red(), e),
+                        }
+                    }
+                    _ => {}
+                }
+            }
         }
     }
 
     fn clear_screen(&self) {
+        // Command-line runs keep their output in the scrollback
+        if !self.interactive {
+            return;
+        }
+
         // Clear the entire screen and scroll buffer
         print!("\x1B[2J\x1B[3J\x1B[1;1H");
-        stdout().flush().unwrap();
+        let _ = stdout().flush();
         
-        execute!(
+        let _ = execute!(
             stdout(),
             terminal::Clear(ClearType::All),
             cursor::MoveTo(0, 0),
             terminal::DisableLineWrap,
-        ).unwrap();
+        );
     }
 
     fn display_menu(&self) -> std::io::Result<()> {
@@ -77,12 +273,32 @@ impl BenchmarkTool {
         println!("{}", "╚══════════════════════════════════════╝".bright_blue());
         println!();
         println!("{}:", "Available Options".bright_yellow());
-        println!("1. {} {}", "►".bright_green(), "System Information");
-        println!("2. {} {}", "►".bright_green(), "CPU Benchmark");
-        println!("3. {} {}", "►".bright_green(), "Memory Benchmark");
-        println!("4. {} {}", "►".bright_green(), "Disk Benchmark");
-        println!("5. {} {}", "►".bright_green(), "Full System Benchmark");
-        println!("6. {} {}", "►".bright_red(), "Exit");
+        println!("1. {} System Information", "►".bright_green());
+        println!("2. {} CPU Benchmark", "►".bright_green());
+        println!("3. {} Memory Benchmark", "►".bright_green());
+        println!("4. {} Disk Benchmarks", "►".bright_green());
+        println!("5. {} Full System Benchmark", "►".bright_green());
+        println!("6. {} Run History", "►".bright_green());
+        println!("7. {} Cache Hierarchy Sweep", "►".bright_green());
+        println!("8. {} Exit", "►".bright_red());
+        println!();
+        println!(
+            "Iterations per benchmark: {} (+{} warm-up) - press 'I' to change",
+            self.settings.iterations, self.settings.warmup_iterations
+        );
+        println!(
+            "Disk target: {} - press 'T' to change",
+            self.settings.disk_dir.as_deref().map_or_else(|| "current directory".to_string(), |d| d.display().to_string())
+        );
+        println!(
+            "Direct I/O (bypass the OS cache): {} - press 'D' to toggle",
+            if self.settings.direct_io { "on" } else { "off" }
+        );
+        println!(
+            "Suite profile: {} ({}) - press 'P' to change",
+            self.settings.profile.name,
+            self.settings.profile.tests.join(", ")
+        );
         println!();
         println!("Press the number of your choice...");
         Ok(())
@@ -92,35 +308,7 @@ impl BenchmarkTool {
         loop {
             self.sys.refresh_all();
             self.clear_screen();
-            
-            println!("{}", "╔══════════════════════════════════════╗".bright_blue());
-            println!("{}", "║        System Information            ║".bright_blue());
-            println!("{}", "╚══════════════════════════════════════╝".bright_blue());
-            println!();
-            
-            // OS Info
-            println!("{}", "Operating System:".bright_yellow());
-            println!("► Name: {}", self.sys.name().unwrap_or_else(|| "Unknown".to_string()));
-            println!("► Version: {}", self.sys.os_version().unwrap_or_else(|| "Unknown".to_string()));
-            println!("► Kernel Version: {}", self.sys.kernel_version().unwrap_or_else(|| "Unknown".to_string()));
-            println!();
-
-            // CPU Info
-            println!("{}", "CPU Information:".bright_yellow());
-            if let Some(cpu) = self.sys.cpus().first() {
-                println!("► Brand: {}", cpu.brand());
-                println!("► Cores: {}", self.sys.cpus().len());
-                println!("► Frequency: {} MHz", cpu.frequency());
-                println!("► CPU Usage: {:.1}%", cpu.cpu_usage());
-            }
-            println!();
-
-            // Memory Info
-            println!("{}", "Memory Information:".bright_yellow());
-            println!("► Total RAM: {:.2} GB", self.sys.total_memory() as f64 / 1024.0 / 1024.0);
-            println!("► Available RAM: {:.2} GB", self.sys.available_memory() as f64 / 1024.0 / 1024.0);
-            println!("► Used RAM: {:.2} GB", (self.sys.total_memory() - self.sys.available_memory()) as f64 / 1024.0 / 1024.0);
-            println!("► Memory Usage: {:.1}%", (1.0 - (self.sys.available_memory() as f64 / self.sys.total_memory() as f64)) * 100.0);
+            self.print_system_info();
 
             self.display_back_button();
             println!("\nPress 'R' to refresh information");
@@ -135,484 +323,417 @@ impl BenchmarkTool {
         }
     }
 
+    fn print_system_info(&self) {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║        System Information            ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+        
+        // OS Info
+        println!("{}", "Operating System:".bright_yellow());
+        println!("► Name: {}", self.sys.name().unwrap_or_else(|| "Unknown".to_string()));
+        println!("► Version: {}", self.sys.os_version().unwrap_or_else(|| "Unknown".to_string()));
+        println!("► Kernel Version: {}", self.sys.kernel_version().unwrap_or_else(|| "Unknown".to_string()));
+        println!();
+
+        // CPU Info
+        println!("{}", "CPU Information:".bright_yellow());
+        if let Some(cpu) = self.sys.cpus().first() {
+            println!("► Brand: {}", cpu.brand());
+            println!("► Cores: {}", self.sys.cpus().len());
+            println!("► Frequency: {} MHz", cpu.frequency());
+            println!("► CPU Usage: {:.1}%", cpu.cpu_usage());
+        }
+        println!();
+
+        // Memory Info
+        println!("{}", "Memory Information:".bright_yellow());
+        println!("► Total RAM: {:.2} GB", self.sys.total_memory() as f64 / 1024.0 / 1024.0);
+        println!("► Available RAM: {:.2} GB", self.sys.available_memory() as f64 / 1024.0 / 1024.0);
+        println!("► Used RAM: {:.2} GB", (self.sys.total_memory() - self.sys.available_memory()) as f64 / 1024.0 / 1024.0);
+        println!("► Memory Usage: {:.1}%", (1.0 - (self.sys.available_memory() as f64 / self.sys.total_memory() as f64)) * 100.0);
+    }
+
     fn run_cpu_benchmark(&mut self) {
         loop {
             self.clear_screen();
+            let outcome = self.measure(&["cpu"], Self::cpu_benchmark_pass);
+            self.show_outcome(&outcome);
 
-            println!("{}", "╔══════════════════════════════════════╗".bright_blue());
-            println!("{}", "║          CPU Benchmark               ║".bright_blue());
-            println!("{}", "╚══════════════════════════════════════╝".bright_blue());
-            println!();
-
-            // Initialize progress bar
-            let pb = ProgressBar::new(100);
-            pb.set_style(ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
-                .unwrap()
-                .progress_chars("#>-"));
-
-            // Prepare benchmark parameters
-            let duration = Duration::from_secs(5);
-            let start_time = Instant::now();
-            let mut operations = 0u64;
-
-            // CPU intensive calculations
-            println!("{}", "Running CPU stress test...".bright_yellow());
-            while start_time.elapsed() < duration {
-                // Complex mathematical operations
-                let mut rng = rand::thread_rng();
-                let n: f64 = rng.gen();
-                let _result = (0..1000).fold(n, |acc, _| {
-                    (acc.sqrt().sin() * acc.cos()).exp()
-                });
-                
-                operations += 1;
-                if operations % 100 == 0 {
-                    pb.set_position((start_time.elapsed().as_secs_f64() / duration.as_secs_f64() * 100.0) as u64);
-                }
+            if !self.wait_on_results(&outcome) {
+                break;
             }
-            pb.finish_with_message("Benchmark complete");
+        }
+    }
 
-            // Calculate score
-            let score = operations as f64 / duration.as_secs_f64();
-            println!();
-            println!("Benchmark Results:");
-            println!("► Operations per second: {:.2}", score);
-            println!("► Total operations: {}", operations);
-            println!("► Time elapsed: {:.2} seconds", start_time.elapsed().as_secs_f64());
-            
-            // CPU Usage after benchmark
-            self.sys.refresh_cpu();
-            if let Some(cpu) = self.sys.cpus().first() {
-                println!("► Current CPU Usage: {:.1}%", cpu.cpu_usage());
-            }
-
-            // Performance Rating
-            let rating = match score {
-                s if s > 1_000_000.0 => "Excellent".bright_green(),
-                s if s > 750_000.0 => "Very Good".bright_blue(),
-                s if s > 500_000.0 => "Good".bright_yellow(),
-                s if s > 250_000.0 => "Fair".bright_yellow(),
-                _ => "Needs Improvement".bright_red(),
-            };
-            println!("► Performance Rating: {}", rating);
-            println!();
+    /// Runs `benchmark` once with a progress bar per step, adding its results to `results`
+    fn run_benchmark(&mut self, benchmark: &mut dyn Benchmark, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        let progress = BarProgress::new();
+        let mut ctx = Context::new(&mut self.sys, &self.settings, &progress);
+        let outcome = benchmark::run_into(benchmark, &mut ctx, results);
+        progress.finish();
+        outcome
+    }
 
-            self.display_back_button();
-            println!("\nPress 'R' to run the benchmark again");
+    fn cpu_benchmark_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║          CPU Benchmark               ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
 
-            if let Ok(Event::Key(key_event)) = event::read() {
-                match key_event.code {
-                    KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => break,
-                    KeyCode::Char('r') | KeyCode::Char('R') => continue,
-                    _ => {}
-                }
-            }
+        let start_time = Instant::now();
+        self.run_benchmark(&mut CpuBenchmark, results)?;
+
+        let score = results.cpu_score;
+        println!();
+        println!("Benchmark Results:");
+        println!("► Single-core operations per second: {:.2}", score);
+        println!("► All-core operations per second: {:.2}", results.cpu_multi_score);
+        println!("► Time elapsed: {:.2} seconds", start_time.elapsed().as_secs_f64());
+        println!();
+        ui::cpu::print_scaling_table(&results.cpu_scaling);
+        println!();
+
+        // CPU Usage after benchmark
+        self.sys.refresh_cpu();
+        if let Some(cpu) = self.sys.cpus().first() {
+            println!("► Current CPU Usage: {:.1}%", cpu.cpu_usage());
         }
+
+        // Score against the reference machine
+        self.print_score(results, &["cpu"], "cpu");
+        println!();
+        Ok(())
     }
 
     fn run_memory_benchmark(&mut self) {
         loop {
             self.clear_screen();
+            let outcome = self.measure(&["memory"], Self::memory_benchmark_pass);
+            self.show_outcome(&outcome);
 
-            println!("{}", "╔══════════════════════════════════════╗".bright_blue());
-            println!("{}", "║        Memory Benchmark              ║".bright_blue());
-            println!("{}", "╚══════════════════════════════════════╝".bright_blue());
-            println!();
+            if !self.wait_on_results(&outcome) {
+                break;
+            }
+        }
+    }
 
-            // Initialize progress bars
-            let pb = ProgressBar::new(100);
-            pb.set_style(ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
-                .unwrap()
-                .progress_chars("#>-"));
-
-            println!("{}", "Allocating memory for test...".bright_yellow());
-            let mut data = vec![0u8; MEMORY_TEST_SIZE];
-            
-            // Write speed test
-            println!("{}", "\nTesting write speed...".bright_yellow());
-            let write_start = Instant::now();
-            let mut rng = rand::thread_rng();
-            
-            for (i, chunk) in data.chunks_mut(CHUNK_SIZE).enumerate() {
-                // Fill with random data
-                for byte in chunk.iter_mut() {
-                    *byte = rng.gen();
-                }
-                pb.set_position((i * 100 / (MEMORY_TEST_SIZE / CHUNK_SIZE)) as u64);
-            }
-            let write_duration = write_start.elapsed();
-            let write_speed = MEMORY_TEST_SIZE as f64 / write_duration.as_secs_f64() / 1024.0 / 1024.0;
-            pb.finish_with_message("Write test complete");
-
-            // Read speed test
-            println!("{}", "\nTesting read speed...".bright_yellow());
-            pb.set_position(0);
-            let read_start = Instant::now();
-            let mut checksum = 0u64;
-
-            for (i, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
-                // Read and perform a simple checksum
-                for &byte in chunk {
-                    checksum = checksum.wrapping_add(byte as u64);
-                }
-                pb.set_position((i * 100 / (MEMORY_TEST_SIZE / CHUNK_SIZE)) as u64);
-            }
-            let read_duration = read_start.elapsed();
-            let read_speed = MEMORY_TEST_SIZE as f64 / read_duration.as_secs_f64() / 1024.0 / 1024.0;
-            pb.finish_with_message("Read test complete");
-
-            // Display results
-            println!("\nMemory Benchmark Results:");
-            println!("► Write Speed: {:.2} MB/s", write_speed);
-            println!("► Read Speed: {:.2} MB/s", read_speed);
-            println!("► Average Speed: {:.2} MB/s", (write_speed + read_speed) / 2.0);
-            
-            // Memory latency test
-            println!("\nTesting memory latency...");
-            let latency_start = Instant::now();
-            let mut pointer = 0usize;
-            for _ in 0..1_000_000 {
-                pointer = data[pointer] as usize % MEMORY_TEST_SIZE;
-            }
-            let latency = latency_start.elapsed().as_nanos() as f64 / 1_000_000.0;
-            println!("► Memory Latency: {:.2} ns", latency);
-
-            // Performance Rating
-            let avg_speed = (write_speed + read_speed) / 2.0;
-            let rating = match avg_speed {
-                s if s > 10000.0 => "Excellent".bright_green(),
-                s if s > 7500.0 => "Very Good".bright_blue(),
-                s if s > 5000.0 => "Good".bright_yellow(),
-                s if s > 2500.0 => "Fair".bright_yellow(),
-                _ => "Needs Improvement".bright_red(),
-            };
-            println!("► Performance Rating: {}", rating);
-            println!();
+    fn memory_benchmark_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║        Memory Benchmark              ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
 
-            // Memory Usage
-            self.sys.refresh_memory();
-            println!("Current Memory Status:");
-            println!("► Used Memory: {:.2} GB", (self.sys.total_memory() - self.sys.available_memory()) as f64 / 1024.0 / 1024.0);
-            println!("► Available Memory: {:.2} GB", self.sys.available_memory() as f64 / 1024.0 / 1024.0);
-            println!("► Memory Usage: {:.1}%", (1.0 - (self.sys.available_memory() as f64 / self.sys.total_memory() as f64)) * 100.0);
+        self.run_benchmark(&mut MemoryBenchmark::default(), results)?;
 
-            self.display_back_button();
-            println!("\nPress 'R' to run the benchmark again");
+        // Display results
+        let (write_speed, read_speed) = (results.memory_write_speed, results.memory_read_speed);
+        println!("\nMemory Benchmark Results:");
+        println!("► Write Speed: {:.2} MB/s", write_speed);
+        println!("► Read Speed: {:.2} MB/s", read_speed);
+        println!("► Average Speed: {:.2} MB/s", (write_speed + read_speed) / 2.0);
+        println!("► Memory Latency: {:.2} ns per dependent load", results.memory_latency);
+        println!();
+        ui::memory::print_stream_table(&results.stream);
 
-            if let Ok(Event::Key(key_event)) = event::read() {
-                match key_event.code {
-                    KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => break,
-                    KeyCode::Char('r') | KeyCode::Char('R') => continue,
-                    _ => {}
-                }
+        // Score against the reference machine
+        self.print_score(results, &["memory"], "memory");
+        println!();
+
+        // Memory Usage
+        self.sys.refresh_memory();
+        println!("Current Memory Status:");
+        println!("► Used Memory: {:.2} GB", (self.sys.total_memory() - self.sys.available_memory()) as f64 / 1024.0 / 1024.0);
+        println!("► Available Memory: {:.2} GB", self.sys.available_memory() as f64 / 1024.0 / 1024.0);
+        println!("► Memory Usage: {:.1}%", (1.0 - (self.sys.available_memory() as f64 / self.sys.total_memory() as f64)) * 100.0);
+        Ok(())
+    }
+
+    fn run_cache_sweep(&mut self) {
+        loop {
+            self.clear_screen();
+            let outcome = self.measure(&["cache"], Self::cache_sweep_pass);
+            self.show_outcome(&outcome);
+
+            if !self.wait_on_results(&outcome) {
+                break;
             }
         }
     }
 
-    fn run_disk_benchmark(&mut self) {
+    fn cache_sweep_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║      Cache Hierarchy Sweep           ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+
+        self.run_benchmark(&mut CacheSweep::default(), results)?;
+
+        println!();
+        ui::memory::print_sweep(&results.cache_sweep, &results.cache_levels);
+        Ok(())
+    }
+
+    /// The volume the disk test ran on, and whether its reads could have come from the cache
+    fn print_disk_target(&self, results: &BenchmarkResults, cached_reads: bool) {
+        if let Some(target) = &results.disk_target {
+            println!("► Target: {}", target.label());
+        }
+        if cached_reads && !self.settings.direct_io {
+            println!("{}", "► Reads are likely served from the OS cache; enable direct I/O to measure the drive".bright_black());
+        }
+        println!();
+    }
+
+    /// Score of one category of a single benchmark run against the reference machine
+    fn print_score(&self, results: &BenchmarkResults, benchmarks: &[&str], category: &str) {
+        let score = CompositeScore::compute(results, benchmarks, &self.settings.profile.score.weights);
+        print_category_score(score.as_ref(), category);
+    }
+
+    fn show_disk_menu(&mut self) {
         loop {
             self.clear_screen();
-
             println!("{}", "╔══════════════════════════════════════╗".bright_blue());
-            println!("{}", "║         Disk Benchmark               ║".bright_blue());
+            println!("{}", "║         Disk Benchmarks              ║".bright_blue());
             println!("{}", "╚══════════════════════════════════════╝".bright_blue());
             println!();
-
-            let test_file = "disk_benchmark_test.tmp";
-            let pb = ProgressBar::new(100);
-            pb.set_style(ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
-                .unwrap()
-                .progress_chars("#>-"));
-
-            // Sequential Write Test
-            println!("{}", "Running Sequential Write Test...".bright_yellow());
-            let mut file = OpenOptions::new()
-                .read(true)
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(test_file)
-                .unwrap();
-
-            let write_start = Instant::now();
-            let mut rng = rand::thread_rng();
-            let mut buffer = vec![0u8; DISK_CHUNK_SIZE];
-            let mut total_written = 0;
-
-            while total_written < DISK_TEST_SIZE {
-                rng.fill(&mut buffer[..]);
-                file.write_all(&buffer).unwrap();
-                total_written += DISK_CHUNK_SIZE;
-                pb.set_position((total_written as f64 / DISK_TEST_SIZE as f64 * 100.0) as u64);
-            }
-            file.sync_all().unwrap();
-            let seq_write_speed = DISK_TEST_SIZE as f64 / write_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
-            pb.finish_with_message("Sequential write complete");
-
-            // Sequential Read Test
-            println!("\n{}", "Running Sequential Read Test...".bright_yellow());
-            pb.set_position(0);
-            file.seek(SeekFrom::Start(0)).unwrap();
-            let read_start = Instant::now();
-            let mut total_read = 0;
-            let mut buffer = vec![0u8; DISK_CHUNK_SIZE];
-
-            while total_read < DISK_TEST_SIZE {
-                file.read_exact(&mut buffer).unwrap();
-                total_read += DISK_CHUNK_SIZE;
-                pb.set_position((total_read as f64 / DISK_TEST_SIZE as f64 * 100.0) as u64);
-            }
-            let seq_read_speed = DISK_TEST_SIZE as f64 / read_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
-            pb.finish_with_message("Sequential read complete");
-
-            // Random Read/Write Test
-            println!("\n{}", "Running Random Access Test...".bright_yellow());
-            pb.set_position(0);
-            let random_start = Instant::now();
-            let mut operations = 0;
-            let num_random_ops = 1000;
-
-            for i in 0..num_random_ops {
-                let pos = rng.gen_range(0..DISK_TEST_SIZE - DISK_CHUNK_SIZE) as u64;
-                file.seek(SeekFrom::Start(pos)).unwrap();
-                
-                if i % 2 == 0 {
-                    file.read_exact(&mut buffer).unwrap();
-                } else {
-                    rng.fill(&mut buffer[..]);
-                    file.write_all(&buffer).unwrap();
-                }
-                operations += 1;
-                pb.set_position((operations as f64 / num_random_ops as f64 * 100.0) as u64);
-            }
-            let random_iops = operations as f64 / random_start.elapsed().as_secs_f64();
-            pb.finish_with_message("Random access test complete");
-
-            // Clean up
-            drop(file);
-            std::fs::remove_file(test_file).unwrap();
-
-            // Display Results
-            println!("\nDisk Benchmark Results:");
-            println!("► Sequential Write Speed: {:.2} MB/s", seq_write_speed);
-            println!("► Sequential Read Speed: {:.2} MB/s", seq_read_speed);
-            println!("► Random Access Speed: {:.2} IOPS", random_iops);
-            println!("► Average Sequential Speed: {:.2} MB/s", (seq_write_speed + seq_read_speed) / 2.0);
-
-            // Performance Rating
-            let avg_speed = (seq_write_speed + seq_read_speed) / 2.0;
-            let rating = match avg_speed {
-                s if s > 1000.0 => "Excellent".bright_green(),
-                s if s > 500.0 => "Very Good".bright_blue(),
-                s if s > 250.0 => "Good".bright_yellow(),
-                s if s > 100.0 => "Fair".bright_yellow(),
-                _ => "Needs Improvement".bright_red(),
-            };
-            println!("► Performance Rating: {}", rating);
-
-            // Disk Information
-            self.sys.refresh_disks();
-            println!("\nDisk Information:");
-            for disk in self.sys.disks() {
-                println!("► Disk Name: {}", disk.name().to_string_lossy());
-                println!("  - Total Space: {:.2} GB", disk.total_space() as f64 / 1024.0 / 1024.0 / 1024.0);
-                println!("  - Available Space: {:.2} GB", disk.available_space() as f64 / 1024.0 / 1024.0 / 1024.0);
-                println!("  - File System: {}", String::from_utf8_lossy(disk.file_system()));
-            }
-
+            println!("1. {} Standard Disk Benchmark", "►".bright_green());
+            let block_sizes = self.settings.profile.sweep_block_sizes();
+            println!(
+                "2. {} Block-Size Sweep ({} to {})",
+                "►".bright_green(),
+                memory::format_size(block_sizes.first().copied().unwrap_or(0)),
+                memory::format_size(block_sizes.last().copied().unwrap_or(0))
+            );
+            println!(
+                "3. {} Queue Depth Scaling (QD {})",
+                "►".bright_green(),
+                self.settings.queue_depths.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("/")
+            );
+            println!("4. {} Durable Writes (append + fsync)", "►".bright_green());
+            println!("5. {} Metadata Operations (create/stat/rename/delete)", "►".bright_green());
             self.display_back_button();
-            println!("\nPress 'R' to run the benchmark again");
 
             if let Ok(Event::Key(key_event)) = event::read() {
                 match key_event.code {
+                    KeyCode::Char('1') => self.run_disk_benchmark(),
+                    KeyCode::Char('2') => self.run_disk_sweep(),
+                    KeyCode::Char('3') => self.run_queue_depth_test(),
+                    KeyCode::Char('4') => self.run_durable_write_test(),
+                    KeyCode::Char('5') => self.run_metadata_test(),
                     KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => break,
-                    KeyCode::Char('r') | KeyCode::Char('R') => continue,
                     _ => {}
                 }
             }
         }
     }
 
-    fn run_full_benchmark(&mut self) -> std::io::Result<()> {
+    fn run_disk_sweep(&mut self) {
         loop {
             self.clear_screen();
+            let outcome = self.measure(&["disk-sweep"], Self::disk_sweep_pass);
+            self.show_outcome(&outcome);
 
-            println!("{}", "╔══════════════════════════════════════╗".bright_blue());
-            println!("{}", "║      Full System Benchmark           ║".bright_blue());
-            println!("{}", "╚══════════════════════════════════════╝".bright_blue());
-            println!();
-            
-            let mut results = BenchmarkResults::default();
-            let start_time = Local::now();
-
-            // System Information
-            println!("{}", "System Information:".bright_yellow());
-            println!("► OS: {} {}", 
-                self.sys.name().unwrap_or_else(|| "Unknown".to_string()),
-                self.sys.os_version().unwrap_or_else(|| "Unknown".to_string()));
-            if let Some(cpu) = self.sys.cpus().first() {
-                println!("► CPU: {} ({} cores)", cpu.brand(), self.sys.cpus().len());
+            if !self.wait_on_results(&outcome) {
+                break;
             }
-            println!("► RAM: {:.2} GB", self.sys.total_memory() as f64 / 1024.0 / 1024.0);
-            println!();
+        }
+    }
 
-            // Progress bars setup
-            let m = MultiProgress::new();
-            let sty = ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({msg})")
-                .unwrap()
-                .progress_chars("#>-");
-
-            // CPU Benchmark
-            println!("{}", "Running CPU Benchmark...".bright_yellow());
-            let cpu_pb = m.add(ProgressBar::new(100));
-            cpu_pb.set_style(sty.clone());
-
-            let duration = Duration::from_secs(5);
-            let cpu_start = Instant::now();
-            let mut operations = 0u64;
-
-            while cpu_start.elapsed() < duration {
-                let mut rng = rand::thread_rng();
-                let n: f64 = rng.gen();
-                let _result = (0..1000).fold(n, |acc, _| {
-                    (acc.sqrt().sin() * acc.cos()).exp()
-                });
-                
-                operations += 1;
-                if operations % 100 == 0 {
-                    cpu_pb.set_position((cpu_start.elapsed().as_secs_f64() / duration.as_secs_f64() * 100.0) as u64);
-                }
+    fn disk_sweep_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║      Disk Block-Size Sweep           ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+
+        self.run_benchmark(&mut BlockSizeSweep::default(), results)?;
+
+        println!();
+        self.print_disk_target(results, true);
+        ui::disk::print_block_size_table(&results.disk_block_sizes);
+        println!();
+        ui::disk::print_block_size_charts(&results.disk_block_sizes);
+        Ok(())
+    }
+
+    fn run_queue_depth_test(&mut self) {
+        loop {
+            self.clear_screen();
+            let outcome = self.measure(&["disk-qd"], Self::queue_depth_pass);
+            self.show_outcome(&outcome);
+
+            if !self.wait_on_results(&outcome) {
+                break;
             }
-            results.cpu_score = operations as f64 / duration.as_secs_f64();
-            cpu_pb.finish_with_message("Complete");
-
-            // Memory Benchmark
-            println!("\n{}", "Running Memory Benchmark...".bright_yellow());
-            let mem_pb = m.add(ProgressBar::new(100));
-            mem_pb.set_style(sty.clone());
-
-            let mut data = vec![0u8; MEMORY_TEST_SIZE];
-            
-            // Write test
-            let write_start = Instant::now();
-            let mut rng = rand::thread_rng();
-            for (i, chunk) in data.chunks_mut(CHUNK_SIZE).enumerate() {
-                for byte in chunk.iter_mut() {
-                    *byte = rng.gen();
-                }
-                mem_pb.set_position((i * 100 / (MEMORY_TEST_SIZE / CHUNK_SIZE)) as u64);
+        }
+    }
+
+    fn queue_depth_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║      Disk Queue Depth Scaling        ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+
+        self.run_benchmark(&mut QueueDepthBenchmark::default(), results)?;
+
+        println!();
+        self.print_disk_target(results, true);
+        ui::disk::print_queue_depth_table(&results.disk_queue_depths, self.settings.profile.qd_block_size(), self.settings.io_uring);
+        Ok(())
+    }
+
+    fn run_durable_write_test(&mut self) {
+        loop {
+            self.clear_screen();
+            let outcome = self.measure(&["disk-fsync"], Self::durable_write_pass);
+            self.show_outcome(&outcome);
+
+            if !self.wait_on_results(&outcome) {
+                break;
             }
-            results.memory_write_speed = MEMORY_TEST_SIZE as f64 / write_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
+        }
+    }
 
-            // Read test
-            mem_pb.reset();
-            let read_start = Instant::now();
-            let mut checksum = 0u64;
-            for (i, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
-                for &byte in chunk {
-                    checksum = checksum.wrapping_add(byte as u64);
-                }
-                mem_pb.set_position((i * 100 / (MEMORY_TEST_SIZE / CHUNK_SIZE)) as u64);
-            }
-            results.memory_read_speed = MEMORY_TEST_SIZE as f64 / read_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
-            
-            // Latency test
-            let latency_start = Instant::now();
-            let mut pointer = 0usize;
-            for _ in 0..1_000_000 {
-                pointer = data[pointer] as usize % MEMORY_TEST_SIZE;
-            }
-            results.memory_latency = latency_start.elapsed().as_nanos() as f64 / 1_000_000.0;
-            mem_pb.finish_with_message("Complete");
-
-            // Disk Benchmark
-            println!("\n{}", "Running Disk Benchmark...".bright_yellow());
-            let disk_pb = m.add(ProgressBar::new(100));
-            disk_pb.set_style(sty.clone());
-
-            let test_file = "disk_benchmark_test.tmp";
-            let mut file = OpenOptions::new()
-                .read(true)
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(test_file)?;
-
-            // Sequential Write
-            let mut buffer = vec![0u8; DISK_CHUNK_SIZE];
-            let mut total_written = 0;
-            let write_start = Instant::now();
-
-            while total_written < DISK_TEST_SIZE {
-                rng.fill(&mut buffer[..]);
-                file.write_all(&buffer)?;
-                total_written += DISK_CHUNK_SIZE;
-                disk_pb.set_position((total_written as f64 / DISK_TEST_SIZE as f64 * 100.0) as u64);
-            }
-            file.sync_all()?;
-            results.disk_write_speed = DISK_TEST_SIZE as f64 / write_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
-
-            // Sequential Read
-            disk_pb.reset();
-            file.seek(SeekFrom::Start(0))?;
-            let read_start = Instant::now();
-            let mut total_read = 0;
-
-            while total_read < DISK_TEST_SIZE {
-                file.read_exact(&mut buffer)?;
-                total_read += DISK_CHUNK_SIZE;
-                disk_pb.set_position((total_read as f64 / DISK_TEST_SIZE as f64 * 100.0) as u64);
-            }
-            results.disk_read_speed = DISK_TEST_SIZE as f64 / read_start.elapsed().as_secs_f64() / 1024.0 / 1024.0;
-
-            // Random IO
-            disk_pb.reset();
-            let random_start = Instant::now();
-            let mut operations = 0;
-            let num_random_ops = 1000;
-
-            for i in 0..num_random_ops {
-                let pos = rng.gen_range(0..DISK_TEST_SIZE - DISK_CHUNK_SIZE) as u64;
-                file.seek(SeekFrom::Start(pos))?;
-                
-                if i % 2 == 0 {
-                    file.read_exact(&mut buffer)?;
-                } else {
-                    rng.fill(&mut buffer[..]);
-                    file.write_all(&buffer)?;
-                }
-                operations += 1;
-                disk_pb.set_position((operations as f64 / num_random_ops as f64 * 100.0) as u64);
+    fn durable_write_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║      Durable Write Latency           ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+
+        self.run_benchmark(&mut DurableWriteBenchmark::default(), results)?;
+
+        println!();
+        self.print_disk_target(results, false);
+        ui::disk::print_durable_writes(&results.disk_durable_writes);
+        Ok(())
+    }
+
+    fn run_metadata_test(&mut self) {
+        loop {
+            self.clear_screen();
+            let outcome = self.measure(&["disk-meta"], Self::metadata_pass);
+            self.show_outcome(&outcome);
+
+            if !self.wait_on_results(&outcome) {
+                break;
             }
-            results.disk_iops = operations as f64 / random_start.elapsed().as_secs_f64();
-            disk_pb.finish_with_message("Complete");
+        }
+    }
+
+    fn metadata_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║      File-System Metadata            ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
 
-            // Clean up
-            drop(file);
-            std::fs::remove_file(test_file)?;
+        self.run_benchmark(&mut MetadataBenchmark::default(), results)?;
 
-            // Generate Report
-            self.generate_benchmark_report(&results, start_time)?;
-            
-            self.display_back_button();
-            println!("\nPress 'R' to run the full benchmark again");
+        println!();
+        self.print_disk_target(results, false);
+        ui::disk::print_metadata_table(&results.disk_metadata);
+        Ok(())
+    }
 
-            if let Ok(Event::Key(key_event)) = event::read() {
-                match key_event.code {
-                    KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => break,
-                    KeyCode::Char('r') | KeyCode::Char('R') => continue,
-                    _ => {}
-                }
+    fn run_disk_benchmark(&mut self) {
+        loop {
+            self.clear_screen();
+            let benchmarks = DiskBenchmark::default().benchmarks(&self.settings);
+            let outcome = self.measure(&benchmarks, Self::disk_benchmark_pass);
+            self.show_outcome(&outcome);
+
+            if !self.wait_on_results(&outcome) {
+                break;
             }
         }
+    }
+
+    fn disk_benchmark_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║         Disk Benchmark               ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+
+        self.run_benchmark(&mut DiskBenchmark::default(), results)?;
+
+        // Display Results
+        let (seq_write_speed, seq_read_speed) = (results.disk_write_speed, results.disk_read_speed);
+        println!("\nDisk Benchmark Results:");
+        if let Some(target) = &results.disk_target {
+            println!("► Target: {}", target.label());
+        }
+        println!("► Sequential Write Speed: {:.2} MB/s", seq_write_speed);
+        println!("► Sequential Read Speed: {:.2} MB/s", seq_read_speed);
+        println!("► Average Sequential Speed: {:.2} MB/s", (seq_write_speed + seq_read_speed) / 2.0);
+        println!();
+        ui::disk::print_random_tests(&results.disk_random);
+        print_disk_latencies(results);
+        if self.settings.direct_io {
+            println!();
+            print_cached_vs_direct(results);
+        }
+
+        // Score against the reference machine
+        self.print_score(results, &DiskBenchmark::default().benchmarks(&self.settings), "disk");
+
+        // Disk Information
+        self.sys.refresh_disks();
+        println!("\nDisk Information:");
+        for disk in self.sys.disks() {
+            println!("► Disk Name: {}", disk.name().to_string_lossy());
+            println!("  - Total Space: {:.2} GB", disk.total_space() as f64 / 1024.0 / 1024.0 / 1024.0);
+            println!("  - Available Space: {:.2} GB", disk.available_space() as f64 / 1024.0 / 1024.0 / 1024.0);
+            println!("  - File System: {}", String::from_utf8_lossy(disk.file_system()));
+        }
         Ok(())
     }
 
-    fn generate_benchmark_report(&self, results: &BenchmarkResults, start_time: chrono::DateTime<Local>) -> std::io::Result<()> {
+    fn run_full_benchmark(&mut self) {
+        loop {
+            self.clear_screen();
+            let outcome = self.run_full_suite();
+            self.show_outcome(&outcome);
+
+            if !self.wait_on_results(&outcome) {
+                break;
+            }
+        }
+    }
+
+    /// Runs the tests of the suite profile for the configured iterations, prints the report
+    /// and records it in the history
+    fn run_full_suite(&mut self) -> std::io::Result<BenchmarkReport> {
+        let benchmarks = Suite::from_profile(&self.settings.profile)?.benchmarks(&self.settings);
+        let report = self.measure(&benchmarks, Self::full_benchmark_pass)?;
+        self.generate_benchmark_report(&report)?;
+        self.record_in_history(&report);
+        Ok(report)
+    }
+
+    fn full_benchmark_pass(&mut self, results: &mut BenchmarkResults) -> std::io::Result<()> {
+        println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+        println!("{}", "║      Full System Benchmark           ║".bright_blue());
+        println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+        println!();
+
+        // System Information
+        println!("{}", "System Information:".bright_yellow());
+        println!("► OS: {} {}", 
+            self.sys.name().unwrap_or_else(|| "Unknown".to_string()),
+            self.sys.os_version().unwrap_or_else(|| "Unknown".to_string()));
+        if let Some(cpu) = self.sys.cpus().first() {
+            println!("► CPU: {} ({} cores)", cpu.brand(), self.sys.cpus().len());
+        }
+        println!("► RAM: {:.2} GB", self.sys.total_memory() as f64 / 1024.0 / 1024.0);
+        println!("► Suite: {} ({})", self.settings.profile.name, self.settings.profile.tests.join(", "));
+        println!();
+
+        self.run_benchmark(&mut Suite::from_profile(&self.settings.profile)?, results)
+    }
+
+    fn generate_benchmark_report(&self, report: &BenchmarkReport) -> std::io::Result<()> {
+        let results = &report.results;
+        let ran = |name: &str| report.benchmarks.iter().any(|b| b == name);
         self.clear_screen();
 
         println!("{}", "╔══════════════════════════════════════╗".bright_blue());
@@ -621,71 +742,280 @@ impl BenchmarkTool {
         println!();
 
         println!("Test completed at: {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
-        println!("Duration: {:.2} minutes", Local::now().signed_duration_since(start_time).num_minutes());
+        println!("Duration: {:.2} minutes", Local::now().signed_duration_since(report.started_at).num_minutes());
         println!();
+        for note in &results.preflight_notes {
+            println!("{}", format!("► {}", note).bright_yellow());
+        }
+        if !results.preflight_notes.is_empty() {
+            println!();
+        }
 
         // CPU Results
-        println!("{}", "CPU Performance:".bright_yellow());
-        println!("► Operations/second: {:.2}", results.cpu_score);
-        let cpu_rating = match results.cpu_score {
-            s if s > 1_000_000.0 => "Excellent".bright_green(),
-            s if s > 750_000.0 => "Very Good".bright_blue(),
-            s if s > 500_000.0 => "Good".bright_yellow(),
-            s if s > 250_000.0 => "Fair".bright_yellow(),
-            _ => "Needs Improvement".bright_red(),
-        };
-        println!("► Rating: {}", cpu_rating);
-        println!();
+        if ran("cpu") {
+            println!("{}", "CPU Performance:".bright_yellow());
+            println!("► Single-core operations/second: {:.2}", results.cpu_score);
+            println!("► All-core operations/second: {:.2}", results.cpu_multi_score);
+            if let Some(all_core) = results.cpu_scaling.last() {
+                println!("► Parallel efficiency: {:.1}% on {} threads ({:.2}x speedup)", all_core.efficiency_pct, all_core.threads, all_core.speedup);
+            }
+            print_category_score(report.score.as_ref(), "cpu");
+            println!();
+        }
 
         // Memory Results
-        println!("{}", "Memory Performance:".bright_yellow());
-        println!("► Read Speed: {:.2} MB/s", results.memory_read_speed);
-        println!("► Write Speed: {:.2} MB/s", results.memory_write_speed);
-        println!("► Latency: {:.2} ns", results.memory_latency);
-        let mem_rating = match (results.memory_read_speed + results.memory_write_speed) / 2.0 {
-            s if s > 10000.0 => "Excellent".bright_green(),
-            s if s > 7500.0 => "Very Good".bright_blue(),
-            s if s > 5000.0 => "Good".bright_yellow(),
-            s if s > 2500.0 => "Fair".bright_yellow(),
-            _ => "Needs Improvement".bright_red(),
-        };
-        println!("► Rating: {}", mem_rating);
-        println!();
+        if ran("memory") {
+            println!("{}", "Memory Performance:".bright_yellow());
+            println!("► Read Speed: {:.2} MB/s", results.memory_read_speed);
+            println!("► Write Speed: {:.2} MB/s", results.memory_write_speed);
+            println!("► Latency: {:.2} ns", results.memory_latency);
+            println!("► STREAM Triad: {:.2} GB/s ({:.2} GB/s single-threaded)",
+                results.stream_triad,
+                results.stream.last().map_or(0.0, |r| r.single_thread_gbs));
+            print_category_score(report.score.as_ref(), "memory");
+            println!();
+        }
 
         // Disk Results
-        println!("{}", "Disk Performance:".bright_yellow());
-        println!("► Sequential Read: {:.2} MB/s", results.disk_read_speed);
-        println!("► Sequential Write: {:.2} MB/s", results.disk_write_speed);
-        println!("► Random IO Operations: {:.2} IOPS", results.disk_iops);
-        let disk_rating = match (results.disk_read_speed + results.disk_write_speed) / 2.0 {
-            s if s > 1000.0 => "Excellent".bright_green(),
-            s if s > 500.0 => "Very Good".bright_blue(),
-            s if s > 250.0 => "Good".bright_yellow(),
-            s if s > 100.0 => "Fair".bright_yellow(),
-            _ => "Needs Improvement".bright_red(),
+        if ran("disk") {
+            println!("{}", "Disk Performance:".bright_yellow());
+            if let Some(target) = &results.disk_target {
+                println!("► Target: {}", target.label());
+            }
+            println!("► Sequential Read: {:.2} MB/s", results.disk_read_speed);
+            println!("► Sequential Write: {:.2} MB/s", results.disk_write_speed);
+            println!("► 4K Random Read: {:.2} IOPS (p99 {:.1} µs)", results.disk_random_read_iops, results.disk_read_p99);
+            println!("► 4K Random Write: {:.2} IOPS (p99 {:.1} µs)", results.disk_random_write_iops, results.disk_write_p99);
+            println!(
+                "► 4K {}: {:.2} IOPS",
+                disk::random_test_name(self.settings.mixed_read_pct),
+                results.disk_mixed_iops
+            );
+            if self.settings.direct_io {
+                print_cached_vs_direct(results);
+            }
+            print_category_score(report.score.as_ref(), "disk");
+            println!();
+        }
+
+        // Further tests the suite profile adds
+        if ran("cache") {
+            ui::memory::print_sweep(&results.cache_sweep, &results.cache_levels);
+            println!();
+        }
+        if ran("disk-sweep") {
+            ui::disk::print_block_size_table(&results.disk_block_sizes);
+            println!();
+        }
+        if ran("disk-qd") {
+            ui::disk::print_queue_depth_table(&results.disk_queue_depths, self.settings.profile.qd_block_size(), self.settings.io_uring);
+            println!();
+        }
+        if ran("disk-fsync") {
+            ui::disk::print_durable_writes(&results.disk_durable_writes);
+            println!();
+        }
+        if ran("disk-meta") {
+            ui::disk::print_metadata_table(&results.disk_metadata);
+            println!();
+        }
+
+        // Composite score over every weighted metric that ran
+        if let Some(score) = &report.score {
+            print_composite_score(results, score);
+        }
+
+        Ok(())
+    }
+
+    fn host_name(&self) -> String {
+        self.sys.host_name().unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    fn record_in_history(&self, report: &BenchmarkReport) {
+        let saved = History::open(&self.host_name()).and_then(|mut history| {
+            let id = history.add(report.clone());
+            history.save().map(|_| id)
+        });
+        match saved {
+            Ok(id) => println!("\n{} #{}", "► Saved to history as run".bright_green(), id),
+            Err(e) => println!("\n{} {}", "► Could not save to history:".bright_red(), e),
+        }
+    }
+
+    /// Reads a line of text in raw mode; returns None if the user pressed Esc
+    fn read_line(&self, prompt: &str) -> Option<String> {
+        print!("\n{} ", prompt);
+        stdout().flush().ok()?;
+        let mut line = String::new();
+        loop {
+            if let Ok(Event::Key(key_event)) = event::read() {
+                match key_event.code {
+                    KeyCode::Enter => return Some(line),
+                    KeyCode::Esc => return None,
+                    KeyCode::Backspace if line.pop().is_some() => print!("\x08 \x08"),
+                    KeyCode::Char(c) => {
+                        line.push(c);
+                        print!("{}", c);
+                    }
+                    _ => {}
+                }
+                stdout().flush().ok()?;
+            }
+        }
+    }
+
+    fn change_iterations(&mut self) {
+        let prompts = [
+            ("Measured iterations (at least 1):", 1),
+            ("Warm-up runs:", 0),
+        ];
+        let mut values = [self.settings.iterations, self.settings.warmup_iterations];
+        for (value, (prompt, min)) in values.iter_mut().zip(prompts) {
+            match self.read_line(prompt).map(|line| line.trim().parse::<usize>()) {
+                Some(Ok(n)) if n >= min => *value = n,
+                Some(_) => continue,
+                None => return,
+            }
+        }
+        self.settings.iterations = values[0];
+        self.settings.warmup_iterations = values[1];
+    }
+
+    /// Lets the user pick the volume for the disk benchmark from the detected disks
+    fn choose_disk_target(&mut self) {
+        self.sys.refresh_disks_list();
+        println!("{}", "Disk Benchmark Target:".bright_yellow());
+        for (i, d) in self.sys.disks().iter().enumerate() {
+            println!(
+                "{:>2}. {} {:<20} {:<8} {:>8.2} GB free of {:.2} GB  {}",
+                i + 1,
+                "►".bright_green(),
+                d.mount_point().display(),
+                String::from_utf8_lossy(d.file_system()),
+                d.available_space() as f64 / 1024.0 / 1024.0 / 1024.0,
+                d.total_space() as f64 / 1024.0 / 1024.0 / 1024.0,
+                d.name().to_string_lossy()
+            );
+        }
+        println!(" 0. {} Current directory", "►".bright_green());
+
+        let Some(line) = self.read_line("Volume number, or a directory path:") else {
+            return;
         };
-        println!("► Rating: {}", disk_rating);
-        println!();
-
-        // Overall System Rating
-        println!("{}", "Overall System Rating:".bright_yellow());
-        let overall_score = match (
-            results.cpu_score > 500_000.0,
-            (results.memory_read_speed + results.memory_write_speed) / 2.0 > 5000.0,
-            (results.disk_read_speed + results.disk_write_speed) / 2.0 > 250.0
-        ) {
-            (true, true, true) => "Excellent".bright_green(),
-            (true, true, false) | (true, false, true) | (false, true, true) => "Very Good".bright_blue(),
-            (true, false, false) | (false, true, false) | (false, false, true) => "Good".bright_yellow(),
-            (false, false, false) => "Needs Improvement".bright_red(),
+        let line = line.trim();
+        self.settings.disk_dir = match line.parse::<usize>() {
+            Ok(0) => None,
+            Ok(n) => match self.sys.disks().get(n - 1) {
+                Some(d) => Some(disk::directory_on(&self.sys, d.mount_point())),
+                None => return,
+            },
+            Err(_) if !line.is_empty() => Some(PathBuf::from(line)),
+            Err(_) => return,
         };
-        println!("► Rating: {}", overall_score);
+    }
 
-        Ok(())
+    /// Lets the user switch to a built-in suite profile or load one from a file
+    fn choose_profile(&mut self) {
+        println!("{}", "Suite Profile:".bright_yellow());
+        for (i, (name, _)) in profile::BUILT_IN.iter().enumerate() {
+            let description = Profile::load(name).map(|p| p.description).unwrap_or_default();
+            println!("{:>2}. {} {:<10} {}", i + 1, "►".bright_green(), name, description);
+        }
+
+        let Some(line) = self.read_line("Profile number, or the path of a TOML or JSON suite file:") else {
+            return;
+        };
+        let line = line.trim();
+        let spec = match line.parse::<usize>() {
+            Ok(n) => match profile::BUILT_IN.get(n.wrapping_sub(1)) {
+                Some((name, _)) => *name,
+                None => return,
+            },
+            Err(_) if !line.is_empty() => line,
+            Err(_) => return,
+        };
+        match Profile::load(spec) {
+            Ok(profile) => self.settings = self.overrides.settings(profile),
+            Err(e) => {
+                println!("\n{} {}: {}", "Could not load the suite profile".bright_red(), spec, e);
+                self.display_back_button();
+                let _ = event::read();
+            }
+        }
+    }
+
+    fn show_history(&mut self) {
+        let mut history = match History::open(&self.host_name()) {
+            Ok(history) => history,
+            Err(e) => {
+                println!("{} {}", "Could not read the history:".bright_red(), e);
+                self.display_back_button();
+                let _ = event::read();
+                return;
+            }
+        };
+        let mut selected = 0usize;
+        let mut show_trends = false;
+
+        loop {
+            self.clear_screen();
+
+            println!("{}", "╔══════════════════════════════════════╗".bright_blue());
+            println!("{}", "║          Run History                 ║".bright_blue());
+            println!("{}", "╚══════════════════════════════════════╝".bright_blue());
+            println!();
+
+            if show_trends {
+                history::print_trends(&history);
+            } else {
+                history::print_runs(&history, Some(selected));
+            }
+
+            self.display_back_button();
+            println!("\nPress 'V' to switch between the run list and the trend view");
+            println!("Use ↑/↓ to select a run, 'T' to tag it and 'D' to delete it");
+
+            let Ok(Event::Key(key_event)) = event::read() else {
+                continue;
+            };
+            let selected_id = history.entries().get(selected).map(|entry| entry.id);
+            match key_event.code {
+                KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => break,
+                KeyCode::Char('v') | KeyCode::Char('V') => show_trends = !show_trends,
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Down if selected + 1 < history.entries().len() => selected += 1,
+                KeyCode::Char('d') | KeyCode::Char('D') if !show_trends => {
+                    if let Some(id) = selected_id {
+                        history.remove(id);
+                        selected = selected.min(history.entries().len().saturating_sub(1));
+                        if let Err(e) = history.save() {
+                            println!("{} {}", "Could not save the history:".bright_red(), e);
+                            let _ = event::read();
+                        }
+                    }
+                }
+                KeyCode::Char('t') | KeyCode::Char('T') if !show_trends => {
+                    if let Some(id) = selected_id {
+                        if let Some(tag) = self.read_line("Tag for the selected run:") {
+                            let tag = tag.trim();
+                            if !tag.is_empty() {
+                                history.tag(id, tag);
+                                if let Err(e) = history.save() {
+                                    println!("{} {}", "Could not save the history:".bright_red(), e);
+                                    let _ = event::read();
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 
     fn run(&mut self) -> std::io::Result<()> {
-        terminal::enable_raw_mode()?;
+        let _terminal = ui::terminal::RawTerminal::enable()?;
 
         loop {
             self.clear_screen();
@@ -697,23 +1027,261 @@ impl BenchmarkTool {
                     KeyCode::Char('1') => self.show_system_info(),
                     KeyCode::Char('2') => self.run_cpu_benchmark(),
                     KeyCode::Char('3') => self.run_memory_benchmark(),
-                    KeyCode::Char('4') => self.run_disk_benchmark(),
-                    KeyCode::Char('5') => self.run_full_benchmark()?,
-                    KeyCode::Char('6') | KeyCode::Esc => break,
+                    KeyCode::Char('4') => self.show_disk_menu(),
+                    KeyCode::Char('5') => self.run_full_benchmark(),
+                    KeyCode::Char('6') => self.show_history(),
+                    KeyCode::Char('i') | KeyCode::Char('I') => self.change_iterations(),
+                    KeyCode::Char('t') | KeyCode::Char('T') => self.choose_disk_target(),
+                    KeyCode::Char('d') | KeyCode::Char('D') => self.settings.direct_io = !self.settings.direct_io,
+                    KeyCode::Char('p') | KeyCode::Char('P') => self.choose_profile(),
+                    KeyCode::Char('7') => self.run_cache_sweep(),
+                    KeyCode::Char('8') | KeyCode::Esc => break,
+                    // Raw mode delivers Ctrl-C as a key rather than a signal
+                    KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => break,
                     _ => continue,
                 }
                 self.clear_screen();  // Clear screen after returning from page
             }
         }
 
-        execute!(stdout(), EnableLineWrap)?;
-        terminal::disable_raw_mode()?;
+        Ok(())
+    }
+
+    /// Runs a command-line command and returns the process exit status
+    fn run_command(&mut self, cli: &Cli) -> std::io::Result<i32> {
+        // Load the baseline first so a typo fails before minutes of benchmarking
+        let baseline = cli.baseline.as_deref().map(|name| baseline::load(name).map(|b| (name, b))).transpose()?;
+
+        let report = match cli.command.clone() {
+            Command::SysInfo => {
+                let started_at = Local::now();
+                self.sys.refresh_all();
+                self.print_system_info();
+                BenchmarkReport::new(&self.sys, &self.settings, &[], &BenchmarkResults::default(), started_at)
+            }
+            Command::Cpu => self.measure(&["cpu"], Self::cpu_benchmark_pass)?,
+            Command::Memory => self.measure(&["memory"], Self::memory_benchmark_pass)?,
+            Command::Disk(DiskTest::Standard) => {
+                let benchmarks = DiskBenchmark::default().benchmarks(&self.settings);
+                self.measure(&benchmarks, Self::disk_benchmark_pass)?
+            }
+            Command::Disk(DiskTest::BlockSizeSweep) => self.measure(&["disk-sweep"], Self::disk_sweep_pass)?,
+            Command::Disk(DiskTest::QueueDepth) => self.measure(&["disk-qd"], Self::queue_depth_pass)?,
+            Command::Disk(DiskTest::DurableWrites) => self.measure(&["disk-fsync"], Self::durable_write_pass)?,
+            Command::Disk(DiskTest::Metadata) => self.measure(&["disk-meta"], Self::metadata_pass)?,
+            Command::Cache => self.measure(&["cache"], Self::cache_sweep_pass)?,
+            Command::Full => self.run_full_suite()?,
+            Command::History(action) => return self.run_history_command(action).map(|_| cli::EXIT_OK),
+            Command::Baseline(action) => return self.run_baseline_command(action).map(|_| cli::EXIT_OK),
+            Command::Menu | Command::Help => unreachable!("handled in main"),
+        };
+        if !report.statistics.is_empty() {
+            println!();
+            ui::stats::print_summaries(&report.results, &report.statistics);
+        }
+
+        if let Some(path) = &cli.json {
+            report.write_json(path)?;
+            println!("\n{} {}", "► Results exported to".bright_green(), path.display());
+        }
+        if let Some(name) = &cli.save_baseline {
+            let path = baseline::save(name, &report)?;
+            println!("{} {}", "► Baseline saved to".bright_green(), path.display());
+        }
+
+        if let Some((name, baseline)) = baseline {
+            println!();
+            let comparisons = baseline::compare(&baseline, &report, cli.tolerance_pct);
+            baseline::print_comparison(name, &baseline, &comparisons, cli.tolerance_pct);
+            if comparisons.iter().any(|c| c.regression) {
+                return Ok(cli::EXIT_REGRESSION);
+            }
+        }
+        Ok(cli::EXIT_OK)
+    }
+
+    fn run_baseline_command(&self, action: BaselineAction) -> std::io::Result<()> {
+        match action {
+            BaselineAction::List => baseline::print_list(&baseline::list()?),
+            BaselineAction::Save(name, id) => {
+                let history = History::open(&self.host_name())?;
+                let entry = match id {
+                    Some(id) => history.entries().iter().find(|entry| entry.id == id),
+                    None => history.entries().last(),
+                };
+                let Some(entry) = entry else {
+                    return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "no such run in the history"));
+                };
+                let path = baseline::save(&name, &entry.report)?;
+                println!("{} #{} {} {}", "► Run".bright_green(), entry.id, "saved as baseline at".bright_green(), path.display());
+            }
+            BaselineAction::Delete(name) => {
+                baseline::delete(&name)?;
+                println!("{} '{}'", "► Deleted baseline".bright_green(), name);
+            }
+        }
+        Ok(())
+    }
+
+    fn run_history_command(&self, action: HistoryAction) -> std::io::Result<()> {
+        let mut history = History::open(&self.host_name())?;
+        let found = match &action {
+            HistoryAction::List => {
+                history::print_runs(&history, None);
+                return Ok(());
+            }
+            HistoryAction::Trend => {
+                history::print_trends(&history);
+                return Ok(());
+            }
+            HistoryAction::Delete(id) => history.remove(*id),
+            HistoryAction::Tag(id, tag) => history.tag(*id, tag),
+            HistoryAction::Untag(id, tag) => history.untag(*id, tag),
+        };
+        if !found {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "no run with that ID in the history"));
+        }
+        history.save()?;
+        println!("{}", "► History updated".bright_green());
         Ok(())
     }
 }
 
-fn main() -> std::io::Result<()> {
-    let mut tool = BenchmarkTool::new();
-    tool.run()?;
-    Ok(())
-} 
\ No newline at end of file
+/// A score colored by how it compares with the reference machine's 1000
+fn colored_score(score: f64) -> ColoredString {
+    let text = format!("{:.0}", score);
+    match score {
+        s if s >= score::REFERENCE_SCORE => text.bright_green(),
+        s if s >= score::REFERENCE_SCORE / 2.0 => text.bright_yellow(),
+        _ => text.bright_red(),
+    }
+}
+
+fn print_category_score(score: Option<&CompositeScore>, category: &str) {
+    if let Some(value) = score.and_then(|s| s.category(category)) {
+        println!("► Score: {} (reference machine = {:.0})", colored_score(value), score::REFERENCE_SCORE);
+    }
+}
+
+/// The composite score and the weighted metrics it combines
+fn print_composite_score(results: &BenchmarkResults, score: &CompositeScore) {
+    println!("{}", "Composite Score:".bright_yellow());
+    println!(
+        "{:<22} {:>14} {:>12} {:>8} {:>7}",
+        "Metric", "Result", "Reference", "Score", "Weight"
+    );
+    let metrics = results.metrics();
+    for m in score.metrics.iter().filter(|m| m.weight > 0.0) {
+        let Some(metric) = metrics.iter().find(|metric| metric.key == m.key) else {
+            continue;
+        };
+        println!(
+            "{:<22} {:>14} {:>12} {:>8} {:>7.2}",
+            metric.label,
+            format!("{:.1} {}", m.value, metric.unit),
+            format!("{:.1}", m.reference),
+            colored_score(m.score),
+            m.weight
+        );
+    }
+    println!(
+        "► Composite: {} (weighted geometric mean; reference machine = {:.0})",
+        colored_score(score.overall),
+        score::REFERENCE_SCORE
+    );
+}
+
+/// Percentiles of every disk test, and histograms of the random reads and writes
+fn print_disk_latencies(results: &BenchmarkResults) {
+    let sequential = format!("({})", memory::format_size(DISK_CHUNK_SIZE));
+    let mut rows: Vec<(String, &latency::LatencySummary)> = Vec::new();
+    if let Some(s) = &results.disk_sequential_read_latency {
+        rows.push((format!("Seq Read {}", sequential), s));
+    }
+    if let Some(s) = &results.disk_sequential_write_latency {
+        rows.push((format!("Seq Write {}", sequential), s));
+    }
+    for test in &results.disk_random {
+        match (&test.read_latency, &test.write_latency) {
+            (Some(read), Some(write)) => {
+                rows.push((format!("{} R", test.name), read));
+                rows.push((format!("{} W", test.name), write));
+            }
+            (Some(s), None) | (None, Some(s)) => rows.push((test.name.clone(), s)),
+            (None, None) => {}
+        }
+    }
+    if rows.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "I/O Latency:".bright_yellow());
+    let rows: Vec<(&str, &latency::LatencySummary)> = rows.iter().map(|(label, s)| (label.as_str(), *s)).collect();
+    ui::latency::print_table(&rows);
+    for test in results.disk_random.iter().filter(|t| t.read_pct == 100 || t.read_pct == 0) {
+        if let Some(summary) = test.read_latency.as_ref().or(test.write_latency.as_ref()) {
+            println!();
+            ui::latency::print_histogram(&format!("4K {}", test.name), summary);
+        }
+    }
+}
+
+/// Sequential disk speeds through the OS cache next to those with direct I/O
+fn print_cached_vs_direct(results: &BenchmarkResults) {
+    println!("{:<18} {:>14} {:>14}", "Cached vs. Direct", "Cached", "Direct I/O");
+    println!(
+        "{:<18} {:>9.2} MB/s {:>9.2} MB/s",
+        "Sequential Write", results.disk_write_speed, results.disk_direct_write_speed
+    );
+    println!(
+        "{:<18} {:>9.2} MB/s {:>9.2} MB/s",
+        "Sequential Read", results.disk_read_speed, results.disk_direct_read_speed
+    );
+}
+
+fn main() {
+    let cli = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(message) => {
+            eprintln!("{} {}\n", "error:".bright_red(), message);
+            eprintln!("{}", cli::USAGE);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+
+    ui::terminal::install_interrupt_handler(cli::EXIT_INTERRUPTED);
+    if cli.settings.high_priority {
+        if let Err(e) = platform::raise_priority() {
+            eprintln!("{} could not raise the process priority, running at normal priority: {}", "warning:".bright_yellow(), e);
+        }
+    }
+    if cli.command.runs_benchmarks() || cli.command == Command::Menu {
+        for path in cleanup::remove_stale_files(cli.settings.disk_dir.as_deref()) {
+            println!("{}", format!("► Removed leftover test file {}", path.display()).bright_black());
+        }
+    }
+
+    let result = match cli.command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(cli::EXIT_OK)
+        }
+        Command::Menu => BenchmarkTool::new(true, &cli).run().map(|_| cli::EXIT_OK),
+        _ => BenchmarkTool::new(false, &cli).run_command(&cli),
+    };
+
+    match result {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("{} {}", "error:".bright_red(), e);
+            std::process::exit(cli::EXIT_FAILURE);
+        }
+    }
+} 
+
+/// A report in which `benchmarks` ran and measured `metrics`, for the tests of the modules
+#[cfg(test)]
+fn test_report(benchmarks: &[&str], metrics: &[(&str, f64)]) -> BenchmarkReport {
+    let results = BenchmarkResults::from_metrics(metrics);
+    BenchmarkReport::new(&System::new(), &BenchmarkSettings::default(), benchmarks, &results, Local::now())
+}
@@ -0,0 +1,327 @@
+// Memory latency measurement by chasing a randomized pointer chain, the cache hierarchy
+// sweep built on it, and STREAM-style bandwidth kernels
+
+use std::hint::black_box;
+use std::mem::size_of;
+use std::thread;
+use std::time::Instant;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::cancel;
+
+/// Pointers are placed one per cache line so every hop touches a new line
+const CACHE_LINE_SIZE: usize = 64;
+const SLOTS_PER_LINE: usize = CACHE_LINE_SIZE / size_of::<usize>();
+
+/// Builds a chain over `bytes` of memory in which each cache line holds the index of the
+/// next one. The order is a single random cycle (Sattolo's algorithm), so the chase
+/// visits every line before repeating and the hardware prefetcher cannot predict it.
+pub fn build_pointer_chain(bytes: usize) -> Vec<usize> {
+    let lines = (bytes / CACHE_LINE_SIZE).max(2);
+    let mut order: Vec<usize> = (0..lines).collect();
+    let mut rng = rand::thread_rng();
+    for i in (1..lines).rev() {
+        let j = rng.gen_range(0..i);
+        order.swap(i, j);
+    }
+
+    let mut chain = vec![0usize; lines * SLOTS_PER_LINE];
+    for (line, &next) in order.iter().enumerate() {
+        chain[line * SLOTS_PER_LINE] = next * SLOTS_PER_LINE;
+    }
+    chain
+}
+
+/// Follows the chain for `loads` dependent loads and returns nanoseconds per load
+pub fn chase_latency_ns(chain: &[usize], loads: usize) -> f64 {
+    // One lap first so the measured loads do not include page faults
+    let mut pointer = 0usize;
+    for _ in 0..chain.len() / SLOTS_PER_LINE {
+        pointer = chain[pointer];
+    }
+
+    let start = Instant::now();
+    for _ in 0..loads {
+        pointer = chain[pointer];
+    }
+    let elapsed = start.elapsed();
+    black_box(pointer);
+    elapsed.as_nanos() as f64 / loads as f64
+}
+
+/// Smallest working set in the cache sweep
+pub const SWEEP_MIN_SIZE: usize = 4 * 1024;
+/// Dependent loads timed at each working-set size
+const SWEEP_LOADS: usize = 2_000_000;
+/// Bytes read at each size when measuring bandwidth, repeating small buffers as needed
+const SWEEP_READ_BYTES: usize = 256 * 1024 * 1024;
+/// A size whose latency exceeds the previous one by this factor starts a transition
+const LEVEL_JUMP_RATIO: f64 = 1.25;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CacheSweepPoint {
+    pub size_bytes: usize,
+    pub latency_ns: f64,
+    pub bandwidth_gbs: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CacheLevel {
+    pub name: String,
+    /// Largest swept working set still served at this level's latency
+    pub max_size_bytes: usize,
+    pub latency_ns: f64,
+}
+
+/// Powers of two from `SWEEP_MIN_SIZE` to `max_bytes`, with the 1.5× step in between
+pub fn sweep_sizes(max_bytes: usize) -> Vec<usize> {
+    let mut sizes = Vec::new();
+    let mut size = SWEEP_MIN_SIZE;
+    while size <= max_bytes {
+        sizes.push(size);
+        if size + size / 2 <= max_bytes {
+            sizes.push(size + size / 2);
+        }
+        size *= 2;
+    }
+    sizes
+}
+
+fn read_bandwidth_gbs(buffer: &[usize]) -> f64 {
+    let bytes = std::mem::size_of_val(buffer);
+    let passes = (SWEEP_READ_BYTES / bytes).max(1);
+    let start = Instant::now();
+    for _ in 0..passes {
+        black_box(buffer.iter().fold(0usize, |acc, &v| acc.wrapping_add(v)));
+    }
+    (bytes * passes) as f64 / start.elapsed().as_secs_f64() / 1e9
+}
+
+pub fn measure_sweep_point(size_bytes: usize) -> CacheSweepPoint {
+    let chain = build_pointer_chain(size_bytes);
+    CacheSweepPoint {
+        size_bytes,
+        latency_ns: chase_latency_ns(&chain, SWEEP_LOADS),
+        bandwidth_gbs: read_bandwidth_gbs(&chain),
+    }
+}
+
+/// Median of each point and its neighbours, so a single noisy size does not look like a
+/// cache boundary
+fn smoothed_latencies(points: &[CacheSweepPoint]) -> Vec<f64> {
+    (0..points.len())
+        .map(|i| {
+            let mut window: Vec<f64> = points[i.saturating_sub(1)..(i + 2).min(points.len())]
+                .iter()
+                .map(|p| p.latency_ns)
+                .collect();
+            window.sort_by(|a, b| a.total_cmp(b));
+            window[window.len() / 2]
+        })
+        .collect()
+}
+
+/// Splits the sweep into plateaus of similar latency. Consecutive rising sizes count as
+/// one transition; the last plateau is main memory.
+pub fn detect_levels(points: &[CacheSweepPoint]) -> Vec<CacheLevel> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let latencies = smoothed_latencies(points);
+
+    let mut levels = Vec::new();
+    let mut plateau_latency = latencies[0];
+    let mut in_transition = false;
+    for i in 1..points.len() {
+        if latencies[i] > latencies[i - 1] * LEVEL_JUMP_RATIO {
+            if !in_transition {
+                levels.push(CacheLevel {
+                    name: format!("L{}", levels.len() + 1),
+                    max_size_bytes: points[i - 1].size_bytes,
+                    latency_ns: plateau_latency,
+                });
+            }
+            in_transition = true;
+        } else if in_transition {
+            plateau_latency = latencies[i - 1];
+            in_transition = false;
+        }
+    }
+
+    let last = points.len() - 1;
+    levels.push(CacheLevel {
+        name: if levels.is_empty() { "Memory".to_string() } else { "DRAM".to_string() },
+        max_size_bytes: points[last].size_bytes,
+        latency_ns: if in_transition { latencies[last] } else { plateau_latency },
+    });
+    levels
+}
+
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 * 1024 => format!("{:.1} GB", b as f64 / (1024.0 * 1024.0 * 1024.0)),
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{} KB", b / 1024),
+        b => format!("{} B", b),
+    }
+}
+
+pub const STREAM_KERNELS: [&str; 4] = ["Copy", "Scale", "Add", "Triad"];
+/// Bytes moved per array element by each kernel: two arrays for Copy and Scale, three
+/// for Add and Triad, counted the way STREAM counts them
+const STREAM_BYTES_PER_ELEMENT: [usize; 4] = [16, 16, 24, 24];
+const STREAM_SCALAR: f64 = 3.0;
+/// Timed repetitions of every kernel; as in STREAM, the fastest one is reported
+const STREAM_REPEATS: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StreamResult {
+    pub kernel: String,
+    pub single_thread_gbs: f64,
+    pub multi_thread_gbs: f64,
+    /// Thread count of the multi-threaded run
+    pub threads: usize,
+}
+
+fn timed(kernel: impl FnOnce()) -> f64 {
+    let start = Instant::now();
+    kernel();
+    start.elapsed().as_secs_f64()
+}
+
+/// Best bandwidth of each STREAM kernel in GB/s over three `len`-element arrays, with
+/// the arrays split evenly between `threads` threads
+fn stream_rates(len: usize, threads: usize) -> [f64; 4] {
+    let chunk = len.div_ceil(threads.max(1));
+    let mut a = vec![1.0f64; len];
+    let mut b = vec![2.0f64; len];
+    let mut c = vec![0.0f64; len];
+    let q = STREAM_SCALAR;
+
+    let mut best = [f64::INFINITY; 4];
+    for _ in 0..STREAM_REPEATS {
+        if cancel::requested() {
+            break;
+        }
+        let times = [
+            timed(|| thread::scope(|s| {
+                for (c, a) in c.chunks_mut(chunk).zip(a.chunks(chunk)) {
+                    s.spawn(move || c.copy_from_slice(a));
+                }
+            })),
+            timed(|| thread::scope(|s| {
+                for (b, c) in b.chunks_mut(chunk).zip(c.chunks(chunk)) {
+                    s.spawn(move || b.iter_mut().zip(c).for_each(|(b, &c)| *b = q * c));
+                }
+            })),
+            timed(|| thread::scope(|s| {
+                for ((c, a), b) in c.chunks_mut(chunk).zip(a.chunks(chunk)).zip(b.chunks(chunk)) {
+                    s.spawn(move || c.iter_mut().zip(a).zip(b).for_each(|((c, &a), &b)| *c = a + b));
+                }
+            })),
+            timed(|| thread::scope(|s| {
+                for ((a, b), c) in a.chunks_mut(chunk).zip(b.chunks(chunk)).zip(c.chunks(chunk)) {
+                    s.spawn(move || a.iter_mut().zip(b).zip(c).for_each(|((a, &b), &c)| *a = b + q * c));
+                }
+            })),
+        ];
+        black_box((&a, &b, &c));
+        for (best, time) in best.iter_mut().zip(times) {
+            *best = best.min(time);
+        }
+    }
+
+    let mut rates = [0.0; 4];
+    for (i, rate) in rates.iter_mut().enumerate() {
+        *rate = (STREAM_BYTES_PER_ELEMENT[i] * len) as f64 / best[i] / 1e9;
+    }
+    rates
+}
+
+/// Runs the STREAM kernels on one thread and on `max_threads` threads
+pub fn measure_stream(len: usize, max_threads: usize) -> Vec<StreamResult> {
+    let single = stream_rates(len, 1);
+    let multi = if max_threads > 1 { stream_rates(len, max_threads) } else { single };
+    STREAM_KERNELS
+        .iter()
+        .enumerate()
+        .map(|(i, kernel)| StreamResult {
+            kernel: kernel.to_string(),
+            single_thread_gbs: single[i],
+            multi_thread_gbs: multi[i],
+            threads: max_threads.max(1),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sweep doubling from 4 KB with the given latencies
+    fn sweep(latencies: &[f64]) -> Vec<CacheSweepPoint> {
+        latencies
+            .iter()
+            .enumerate()
+            .map(|(i, &latency_ns)| CacheSweepPoint { size_bytes: SWEEP_MIN_SIZE << i, latency_ns, bandwidth_gbs: 0.0 })
+            .collect()
+    }
+
+    fn summary(levels: &[CacheLevel]) -> Vec<(&str, usize, f64)> {
+        levels.iter().map(|l| (l.name.as_str(), l.max_size_bytes, l.latency_ns)).collect()
+    }
+
+    const KB: usize = 1024;
+    const MB: usize = 1024 * 1024;
+
+    #[test]
+    fn the_pointer_chain_is_one_cycle_through_every_line() {
+        for bytes in [0, CACHE_LINE_SIZE * 2, CACHE_LINE_SIZE * 3, 64 * KB, MB + CACHE_LINE_SIZE] {
+            let chain = build_pointer_chain(bytes);
+            let lines = chain.len() / SLOTS_PER_LINE;
+            let mut visited = vec![false; lines];
+            let mut slot = 0;
+            for _ in 0..lines {
+                assert_eq!(slot % SLOTS_PER_LINE, 0, "every pointer leads to the start of a line");
+                assert!(!visited[slot / SLOTS_PER_LINE], "a line is visited twice before the cycle closes");
+                visited[slot / SLOTS_PER_LINE] = true;
+                slot = chain[slot];
+            }
+            assert_eq!(slot, 0, "the chain returns to the first line after visiting all {}", lines);
+        }
+    }
+
+    #[test]
+    fn finds_each_plateau_of_a_stepped_curve() {
+        let points = sweep(&[1.0, 1.0, 1.0, 1.0, 4.0, 4.0, 4.0, 12.0, 12.0, 12.0, 12.0, 80.0, 80.0, 80.0, 80.0]);
+        assert_eq!(
+            summary(&detect_levels(&points)),
+            [("L1", 32 * KB, 1.0), ("L2", 256 * KB, 4.0), ("L3", 4 * MB, 12.0), ("DRAM", 64 * MB, 80.0)]
+        );
+    }
+
+    #[test]
+    fn a_gradual_rise_is_one_transition() {
+        let points = sweep(&[1.0, 1.0, 1.0, 1.0, 2.0, 4.0, 8.0, 8.0, 8.0, 8.0]);
+        assert_eq!(summary(&detect_levels(&points)), [("L1", 32 * KB, 1.0), ("DRAM", 2 * MB, 8.0)]);
+    }
+
+    #[test]
+    fn a_single_noisy_size_is_not_a_level() {
+        let points = sweep(&[1.0, 1.0, 1.0, 5.0, 1.0, 1.0, 1.0]);
+        assert_eq!(summary(&detect_levels(&points)), [("Memory", 256 * KB, 1.0)]);
+    }
+
+    #[test]
+    fn a_sweep_ending_in_a_transition_reports_its_last_latency() {
+        let points = sweep(&[1.0, 1.0, 1.0, 4.0, 16.0]);
+        assert_eq!(summary(&detect_levels(&points)), [("L1", 16 * KB, 1.0), ("DRAM", 64 * KB, 16.0)]);
+    }
+
+    #[test]
+    fn an_empty_sweep_has_no_levels() {
+        assert!(detect_levels(&[]).is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+// Operating-system specific parts of the benchmarks behind one interface: process
+// priority, page-cache bypass and positional file I/O
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+#[cfg(unix)]
+mod unix;
+#[cfg(unix)]
+use unix as imp;
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use windows as imp;
+
+/// How the page cache is bypassed on this platform, for messages
+pub const UNCACHED_IO: &str = imp::UNCACHED_IO;
+
+/// Raises the scheduling priority of this process so background work disturbs the
+/// measurements less. Threads started afterwards inherit it.
+pub fn raise_priority() -> io::Result<()> {
+    imp::raise_priority()
+}
+
+/// Creates `path` for reading and writing with the OS page cache bypassed
+pub fn open_uncached(path: &Path) -> io::Result<File> {
+    imp::open_uncached(path)
+}
+
+/// Reads `buf.len()` bytes at `offset` without moving a shared file cursor, so several
+/// threads can issue I/O on one file at once
+pub fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    imp::read_at(file, buf, offset)
+}
+
+pub fn write_at(file: &File, buf: &[u8], offset: u64) -> io::Result<()> {
+    imp::write_at(file, buf, offset)
+}
@@ -0,0 +1,56 @@
+// Linux, macOS and other Unix implementations
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+pub const UNCACHED_IO: &str = "O_DIRECT";
+#[cfg(target_os = "macos")]
+pub const UNCACHED_IO: &str = "F_NOCACHE";
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "macos")))]
+pub const UNCACHED_IO: &str = "unsupported";
+
+/// Nice value of a raised priority; going below 0 needs root or CAP_SYS_NICE
+const HIGH_PRIORITY_NICE: libc::c_int = -10;
+
+pub fn raise_priority() -> io::Result<()> {
+    // SAFETY: setpriority only reads its arguments; 0 is the calling process
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, HIGH_PRIORITY_NICE) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+pub fn open_uncached(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(true);
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_DIRECT);
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "macos")))]
+    return Err(io::Error::new(io::ErrorKind::Unsupported, "direct I/O is not supported on this platform"));
+
+    let file = options.open(path)?;
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: plain fcntl on a descriptor owned by `file`
+        if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_NOCACHE, 1) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(file)
+}
+
+pub fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    file.read_exact_at(buf, offset)
+}
+
+pub fn write_at(file: &File, buf: &[u8], offset: u64) -> io::Result<()> {
+    file.write_all_at(buf, offset)
+}
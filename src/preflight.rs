@@ -0,0 +1,131 @@
+// Checks made before a test allocates memory or writes to disk, so that a machine short on
+// either runs a smaller test or gets a clear refusal instead of swapping or filling the disk
+
+use std::io;
+
+use sysinfo::{DiskExt, SystemExt};
+
+use crate::benchmark::Context;
+use crate::disk::{self, DiskTarget};
+use crate::memory::format_size;
+
+/// Share of the available memory one test may allocate; the rest is left to the OS and
+/// other processes so the test does not swap
+const MEMORY_SHARE: f64 = 0.5;
+/// Free space left on the target volume after the test files are written
+const DISK_RESERVE: u64 = 256 * 1024 * 1024;
+
+/// The largest of `wanted`, `wanted / 2`, `wanted / 4` … not below `minimum` and not
+/// smaller than one `block` that fits in `allowed` bytes
+fn fit(wanted: usize, minimum: usize, block: usize, allowed: u64) -> Option<usize> {
+    let floor = minimum.max(block);
+    if wanted < block {
+        return None;
+    }
+    let mut size = wanted;
+    while size as u64 > allowed {
+        if size / 2 < floor {
+            return None;
+        }
+        size /= 2;
+    }
+    Some(size)
+}
+
+/// Records a test that runs with less data than configured
+fn note_scaled(ctx: &mut Context, test: &str, wanted: usize, size: usize, reason: &str) -> usize {
+    if size < wanted {
+        ctx.note(format!("{} scaled down from {} to {} ({})", test, format_size(wanted), format_size(size), reason));
+    }
+    size
+}
+
+/// Bytes `test` may allocate: `wanted` when enough memory is available, otherwise the
+/// largest halving of it that fits, or an error when not even `minimum` fits
+pub fn memory(ctx: &mut Context, test: &str, wanted: usize, minimum: usize) -> io::Result<usize> {
+    ctx.sys.refresh_memory();
+    let available = ctx.sys.available_memory();
+    match fit(wanted, minimum, 1, (available as f64 * MEMORY_SHARE) as u64) {
+        Some(size) => Ok(note_scaled(ctx, test, wanted, size, "low memory")),
+        None => Err(io::Error::new(
+            io::ErrorKind::OutOfMemory,
+            format!(
+                "not enough memory for the {}: it needs {} available, but only {} is",
+                test.to_lowercase(),
+                format_size((minimum as f64 / MEMORY_SHARE) as usize),
+                format_size(available as usize)
+            ),
+        )),
+    }
+}
+
+/// Bytes `test` may write to `target`: `wanted` when the volume has enough free space,
+/// otherwise the largest halving of it that fits, or an error when not even `minimum`
+/// fits. The size never drops below one `block`, the largest I/O the test makes in the
+/// file. Volumes that cannot be identified are not checked.
+pub fn disk_space(
+    ctx: &mut Context,
+    target: &DiskTarget,
+    test: &str,
+    wanted: usize,
+    minimum: usize,
+    block: usize,
+) -> io::Result<usize> {
+    if wanted < block {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the {} file of {} is smaller than one {} block", test.to_lowercase(), format_size(wanted), format_size(block)),
+        ));
+    }
+    ctx.sys.refresh_disks();
+    let Some(volume) = disk::volume_of(ctx.sys, &target.directory) else {
+        return Ok(wanted);
+    };
+    let available = volume.available_space();
+    match fit(wanted, minimum, block, available.saturating_sub(DISK_RESERVE)) {
+        Some(size) => Ok(note_scaled(ctx, test, wanted, size, "low disk space")),
+        None => Err(io::Error::new(
+            io::ErrorKind::StorageFull,
+            format!(
+                "not enough free space on {} for the {}: it needs {}, but only {} is free",
+                target.mount_point.display(),
+                test.to_lowercase(),
+                format_size(minimum.max(block) + DISK_RESERVE as usize),
+                format_size(available as usize)
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fit;
+
+    const MB: usize = 1024 * 1024;
+
+    #[test]
+    fn keeps_a_size_that_already_fits() {
+        assert_eq!(fit(256 * MB, 64 * MB, 4096, 256 * MB as u64), Some(256 * MB));
+        assert_eq!(fit(256 * MB, 64 * MB, 4096, u64::MAX), Some(256 * MB));
+    }
+
+    #[test]
+    fn halves_until_the_size_fits() {
+        assert_eq!(fit(256 * MB, 64 * MB, 4096, 200 * MB as u64), Some(128 * MB));
+        assert_eq!(fit(256 * MB, 64 * MB, 4096, 100 * MB as u64), Some(64 * MB));
+    }
+
+    #[test]
+    fn refuses_when_not_even_the_minimum_fits() {
+        assert_eq!(fit(256 * MB, 64 * MB, 4096, 63 * MB as u64), None);
+        assert_eq!(fit(256 * MB, 64 * MB, 4096, 0), None);
+    }
+
+    #[test]
+    fn never_goes_below_one_block() {
+        // The minimum alone would allow 64 MB, but the test does 128 MB I/Os
+        assert_eq!(fit(512 * MB, 64 * MB, 128 * MB, 100 * MB as u64), None);
+        assert_eq!(fit(512 * MB, 64 * MB, 128 * MB, 200 * MB as u64), Some(128 * MB));
+        assert_eq!(fit(MB, MB, 4 * MB, u64::MAX), None);
+    }
+}
@@ -0,0 +1,511 @@
+// Benchmark suite profiles: which tests the full benchmark runs and the sizes, durations,
+// thread counts, block sizes and target of every test, loaded from TOML or JSON files so
+// that teams can share the same settings
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::disk::DIRECT_IO_ALIGNMENT;
+
+/// Profiles that ship with the tool, by name
+pub const BUILT_IN: [(&str, &str); 3] = [
+    ("quick", include_str!("../profiles/quick.toml")),
+    ("standard", include_str!("../profiles/standard.toml")),
+    ("extended", include_str!("../profiles/extended.toml")),
+];
+
+/// Names a profile can list in `tests`
+pub const TEST_NAMES: [&str; 8] = ["cpu", "memory", "cache", "disk", "disk-sweep", "disk-qd", "disk-fsync", "disk-meta"];
+
+const MB: usize = 1024 * 1024;
+const KB: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub name: String,
+    pub description: String,
+    /// Benchmarks the full benchmark runs, in order
+    pub tests: Vec<String>,
+    /// Measured runs of each benchmark; `--iterations` takes precedence
+    pub iterations: Option<usize>,
+    /// Discarded runs before the measured ones; `--warmup` takes precedence
+    pub warmup_iterations: Option<usize>,
+    pub cpu: CpuProfile,
+    pub memory: MemoryProfile,
+    pub cache: CacheProfile,
+    pub disk: DiskProfile,
+    pub disk_sweep: DiskSweepProfile,
+    pub disk_qd: QueueDepthProfile,
+    pub disk_fsync: DurableWriteProfile,
+    pub disk_meta: MetadataProfile,
+    pub score: ScoreProfile,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CpuProfile {
+    /// Run time per thread count
+    pub duration_secs: f64,
+    /// Most threads the scaling test goes up to; every logical CPU when unset
+    pub max_threads: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MemoryProfile {
+    /// Buffer of the read/write and latency tests
+    pub size_mb: usize,
+    /// Dependent loads timed by the latency test
+    pub latency_loads: usize,
+    /// Size of each of the three STREAM arrays
+    pub stream_array_mb: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheProfile {
+    /// Largest working set, capped at half of the available memory
+    pub max_size_mb: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiskProfile {
+    /// Directory for the test files; `--disk-dir` takes precedence
+    pub dir: Option<PathBuf>,
+    /// Also run the sequential tests with the page cache bypassed; `--direct-io` turns it on
+    pub direct_io: Option<bool>,
+    /// Test file of the sequential and random tests
+    pub size_mb: usize,
+    /// Operations per random test
+    pub random_ops: usize,
+    pub random_block_size_kb: usize,
+    /// Share of reads in the mixed random test; `--mix` takes precedence
+    pub mixed_read_pct: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiskSweepProfile {
+    pub file_size_mb: usize,
+    /// Run time per block size and access pattern
+    pub duration_secs: f64,
+    pub block_sizes_kb: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueueDepthProfile {
+    pub file_size_mb: usize,
+    pub block_size_kb: usize,
+    /// Run time per queue depth and direction
+    pub duration_secs: f64,
+    /// `--queue-depths` takes precedence
+    pub queue_depths: Option<Vec<usize>>,
+    /// `--workers` takes precedence
+    pub workers: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DurableWriteProfile {
+    /// Bytes per append; any size works, as the file is written through the page cache
+    pub append_size_bytes: usize,
+    /// Run time per sync method
+    pub duration_secs: f64,
+    /// Most data appended per sync method
+    pub max_mb: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetadataProfile {
+    /// Operations per operation type
+    pub ops: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreProfile {
+    /// Weights of metrics in the composite score by metric key, replacing the defaults of
+    /// `score::REFERENCE_MACHINE`; 0 leaves a metric out
+    pub weights: BTreeMap<String, f64>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            tests: ["cpu", "memory", "disk"].map(String::from).to_vec(),
+            iterations: None,
+            warmup_iterations: None,
+            cpu: CpuProfile::default(),
+            memory: MemoryProfile::default(),
+            cache: CacheProfile::default(),
+            disk: DiskProfile::default(),
+            disk_sweep: DiskSweepProfile::default(),
+            disk_qd: QueueDepthProfile::default(),
+            disk_fsync: DurableWriteProfile::default(),
+            disk_meta: MetadataProfile::default(),
+            score: ScoreProfile::default(),
+        }
+    }
+}
+
+impl Default for CpuProfile {
+    fn default() -> Self {
+        Self { duration_secs: 5.0, max_threads: None }
+    }
+}
+
+impl Default for MemoryProfile {
+    fn default() -> Self {
+        Self { size_mb: 256, latency_loads: 1_000_000, stream_array_mb: 64 }
+    }
+}
+
+impl Default for CacheProfile {
+    fn default() -> Self {
+        Self { max_size_mb: 1024 }
+    }
+}
+
+impl Default for DiskProfile {
+    fn default() -> Self {
+        Self { dir: None, direct_io: None, size_mb: 512, random_ops: 10_000, random_block_size_kb: 4, mixed_read_pct: None }
+    }
+}
+
+impl Default for DiskSweepProfile {
+    fn default() -> Self {
+        Self {
+            file_size_mb: 256,
+            duration_secs: 1.0,
+            block_sizes_kb: vec![4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 16384],
+        }
+    }
+}
+
+impl Default for QueueDepthProfile {
+    fn default() -> Self {
+        Self { file_size_mb: 256, block_size_kb: 4, duration_secs: 2.0, queue_depths: None, workers: None }
+    }
+}
+
+impl Default for DurableWriteProfile {
+    fn default() -> Self {
+        Self { append_size_bytes: 4096, duration_secs: 3.0, max_mb: 256 }
+    }
+}
+
+impl Default for MetadataProfile {
+    fn default() -> Self {
+        Self { ops: 10_000 }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// `value` of `key` in bytes, or an error when that does not fit in a usize
+fn bytes(key: &str, value: usize, unit: usize) -> io::Result<usize> {
+    value.checked_mul(unit).ok_or_else(|| invalid(format!("'{}' of {} is too large", key, value)))
+}
+
+fn duration(secs: f64) -> Duration {
+    Duration::from_secs_f64(secs)
+}
+
+impl Profile {
+    /// The default profile, the same as the built-in "standard" one
+    pub fn standard() -> Self {
+        Self {
+            name: "standard".to_string(),
+            description: "CPU, memory and standard disk tests with the default sizes".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// A built-in profile by name, or a TOML or JSON file by path; a file that sets no
+    /// name is named after the file
+    pub fn load(spec: &str) -> io::Result<Self> {
+        if let Some((_, text)) = BUILT_IN.iter().find(|(name, _)| *name == spec) {
+            return Self::parse(text, false);
+        }
+        let path = Path::new(spec);
+        let text = std::fs::read_to_string(path)?;
+        let json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let mut profile = Self::parse(&text, json)?;
+        if profile.name.is_empty() {
+            profile.name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        }
+        Ok(profile)
+    }
+
+    fn parse(text: &str, json: bool) -> io::Result<Self> {
+        let profile: Self = if json {
+            serde_json::from_str(text).map_err(|e| invalid(e.to_string()))?
+        } else {
+            toml::from_str(text).map_err(|e| invalid(e.message().to_string()))?
+        };
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Rejects unknown tests and sizes, durations and counts the tests cannot run with
+    fn validate(&self) -> io::Result<()> {
+        if self.tests.is_empty() {
+            return Err(invalid("'tests' lists no benchmark".to_string()));
+        }
+        if let Some(test) = self.tests.iter().find(|t| !TEST_NAMES.contains(&t.as_str())) {
+            return Err(invalid(format!("unknown test '{}' in 'tests' (expected one of {})", test, TEST_NAMES.join(", "))));
+        }
+
+        let positive = [
+            ("iterations", self.iterations.unwrap_or(1)),
+            ("cpu.max_threads", self.cpu.max_threads.unwrap_or(1)),
+            ("memory.size_mb", self.memory.size_mb),
+            ("memory.latency_loads", self.memory.latency_loads),
+            ("memory.stream_array_mb", self.memory.stream_array_mb),
+            ("cache.max_size_mb", self.cache.max_size_mb),
+            ("disk.size_mb", self.disk.size_mb),
+            ("disk.random_ops", self.disk.random_ops),
+            ("disk_sweep.file_size_mb", self.disk_sweep.file_size_mb),
+            ("disk_qd.file_size_mb", self.disk_qd.file_size_mb),
+            ("disk_qd.workers", self.disk_qd.workers.unwrap_or(1)),
+            ("disk_fsync.append_size_bytes", self.disk_fsync.append_size_bytes),
+            ("disk_fsync.max_mb", self.disk_fsync.max_mb),
+            ("disk_meta.ops", self.disk_meta.ops),
+        ];
+        if let Some((key, _)) = positive.iter().find(|(_, value)| *value == 0) {
+            return Err(invalid(format!("'{}' must be at least 1", key)));
+        }
+        if self.disk_qd.queue_depths.as_ref().is_some_and(|depths| depths.is_empty() || depths.contains(&0)) {
+            return Err(invalid("'disk_qd.queue_depths' must list depths of at least 1".to_string()));
+        }
+        if self.disk.mixed_read_pct.is_some_and(|pct| pct > 100) {
+            return Err(invalid("'disk.mixed_read_pct' must be at most 100".to_string()));
+        }
+
+        let durations = [
+            ("cpu.duration_secs", self.cpu.duration_secs),
+            ("disk_sweep.duration_secs", self.disk_sweep.duration_secs),
+            ("disk_qd.duration_secs", self.disk_qd.duration_secs),
+            ("disk_fsync.duration_secs", self.disk_fsync.duration_secs),
+        ];
+        if let Some((key, _)) = durations.iter().find(|(_, secs)| !(secs.is_finite() && *secs > 0.0)) {
+            return Err(invalid(format!("'{}' must be a positive number of seconds", key)));
+        }
+
+        // Direct I/O needs block sizes that are multiples of the device block size
+        let alignment_kb = DIRECT_IO_ALIGNMENT / KB;
+        let block_sizes = [
+            ("disk.random_block_size_kb", self.disk.random_block_size_kb),
+            ("disk_qd.block_size_kb", self.disk_qd.block_size_kb),
+        ]
+        .into_iter()
+        .chain(self.disk_sweep.block_sizes_kb.iter().map(|&kb| ("disk_sweep.block_sizes_kb", kb)));
+        for (key, kb) in block_sizes {
+            if kb == 0 || kb % alignment_kb != 0 {
+                return Err(invalid(format!("'{}' must be a multiple of {} KB, not {}", key, alignment_kb, kb)));
+            }
+        }
+        for (key, weight) in &self.score.weights {
+            if !crate::score::REFERENCE_MACHINE.iter().any(|r| r.key == key) {
+                return Err(invalid(format!("unknown metric '{}' in 'score.weights'", key)));
+            }
+            if !(weight.is_finite() && *weight >= 0.0) {
+                return Err(invalid(format!("'score.weights.{}' must be a number of at least 0", key)));
+            }
+        }
+        if self.disk_sweep.block_sizes_kb.is_empty() {
+            return Err(invalid("'disk_sweep.block_sizes_kb' lists no block size".to_string()));
+        }
+        // Every size must fit in a usize once converted to bytes
+        let sizes = [
+            ("memory.size_mb", self.memory.size_mb, MB),
+            ("memory.stream_array_mb", self.memory.stream_array_mb, MB),
+            ("cache.max_size_mb", self.cache.max_size_mb, MB),
+            ("disk.size_mb", self.disk.size_mb, MB),
+            ("disk.random_block_size_kb", self.disk.random_block_size_kb, KB),
+            ("disk_sweep.file_size_mb", self.disk_sweep.file_size_mb, MB),
+            ("disk_qd.file_size_mb", self.disk_qd.file_size_mb, MB),
+            ("disk_qd.block_size_kb", self.disk_qd.block_size_kb, KB),
+            ("disk_fsync.max_mb", self.disk_fsync.max_mb, MB),
+        ]
+        .into_iter()
+        .chain(self.disk_sweep.block_sizes_kb.iter().map(|&kb| ("disk_sweep.block_sizes_kb", kb, KB)));
+        for (key, value, unit) in sizes {
+            bytes(key, value, unit)?;
+        }
+        // Random I/O picks whole blocks of the test file, so it must hold at least one;
+        // the durable-write file must hold at least one append
+        let larger_than_file = [
+            ("disk.random_block_size_kb", self.disk.random_block_size_kb, KB, "disk.size_mb", self.disk.size_mb),
+            ("disk_qd.block_size_kb", self.disk_qd.block_size_kb, KB, "disk_qd.file_size_mb", self.disk_qd.file_size_mb),
+            ("disk_fsync.append_size_bytes", self.disk_fsync.append_size_bytes, 1, "disk_fsync.max_mb", self.disk_fsync.max_mb),
+        ]
+        .into_iter()
+        .chain(
+            self.disk_sweep
+                .block_sizes_kb
+                .iter()
+                .map(|&kb| ("disk_sweep.block_sizes_kb", kb, KB, "disk_sweep.file_size_mb", self.disk_sweep.file_size_mb)),
+        );
+        for (block_key, block, unit, file_key, mb) in larger_than_file {
+            if bytes(block_key, block, unit)? > bytes(file_key, mb, MB)? {
+                return Err(invalid(format!("'{}' of {} is larger than '{}' of {}", block_key, block, file_key, mb)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn cpu_duration(&self) -> Duration {
+        duration(self.cpu.duration_secs)
+    }
+
+    pub fn memory_size(&self) -> usize {
+        self.memory.size_mb * MB
+    }
+
+    /// Elements of each STREAM array
+    pub fn stream_array_len(&self) -> usize {
+        self.memory.stream_array_mb * MB / std::mem::size_of::<f64>()
+    }
+
+    pub fn cache_max_size(&self) -> usize {
+        self.cache.max_size_mb * MB
+    }
+
+    pub fn disk_size(&self) -> usize {
+        self.disk.size_mb * MB
+    }
+
+    pub fn disk_random_block_size(&self) -> usize {
+        self.disk.random_block_size_kb * KB
+    }
+
+    pub fn sweep_file_size(&self) -> usize {
+        self.disk_sweep.file_size_mb * MB
+    }
+
+    pub fn sweep_duration(&self) -> Duration {
+        duration(self.disk_sweep.duration_secs)
+    }
+
+    /// Sweep block sizes in bytes, smallest first
+    pub fn sweep_block_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.disk_sweep.block_sizes_kb.iter().map(|kb| kb * KB).collect();
+        sizes.sort_unstable();
+        sizes.dedup();
+        sizes
+    }
+
+    pub fn qd_file_size(&self) -> usize {
+        self.disk_qd.file_size_mb * MB
+    }
+
+    pub fn qd_block_size(&self) -> usize {
+        self.disk_qd.block_size_kb * KB
+    }
+
+    pub fn qd_duration(&self) -> Duration {
+        duration(self.disk_qd.duration_secs)
+    }
+
+    pub fn fsync_append_size(&self) -> usize {
+        self.disk_fsync.append_size_bytes
+    }
+
+    pub fn fsync_duration(&self) -> Duration {
+        duration(self.disk_fsync.duration_secs)
+    }
+
+    pub fn fsync_max_bytes(&self) -> usize {
+        self.disk_fsync.max_mb * MB
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A profile file holding only `toml`, so every other key has its default
+    fn standard_with(toml: &str) -> io::Result<Profile> {
+        Profile::parse(toml, false)
+    }
+
+    fn error(toml: &str) -> String {
+        standard_with(toml).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn every_built_in_profile_parses() {
+        for (name, _) in BUILT_IN {
+            let profile = Profile::load(name).unwrap();
+            assert_eq!(profile.name, name);
+        }
+    }
+
+    #[test]
+    fn the_standard_profile_file_matches_the_defaults() {
+        let (_, text) = BUILT_IN.iter().find(|(name, _)| *name == "standard").unwrap();
+        assert_eq!(Profile::parse(text, false).unwrap(), Profile::standard());
+    }
+
+    #[test]
+    fn rejects_zero_sizes_and_counts() {
+        assert_eq!(error("[memory]\nsize_mb = 0"), "'memory.size_mb' must be at least 1");
+        assert_eq!(error("[disk_fsync]\nappend_size_bytes = 0"), "'disk_fsync.append_size_bytes' must be at least 1");
+        assert_eq!(error("[disk_qd]\nblock_size_kb = 0"), "'disk_qd.block_size_kb' must be a multiple of 4 KB, not 0");
+        assert_eq!(error("[cpu]\nduration_secs = 0.0"), "'cpu.duration_secs' must be a positive number of seconds");
+    }
+
+    #[test]
+    fn rejects_blocks_the_direct_io_tests_cannot_use() {
+        assert_eq!(error("[disk]\nrandom_block_size_kb = 6"), "'disk.random_block_size_kb' must be a multiple of 4 KB, not 6");
+        assert_eq!(
+            error("[disk_sweep]\nblock_sizes_kb = [4, 10]"),
+            "'disk_sweep.block_sizes_kb' must be a multiple of 4 KB, not 10"
+        );
+    }
+
+    #[test]
+    fn durable_writes_accept_any_append_size() {
+        let profile = standard_with("[disk_fsync]\nappend_size_bytes = 512").unwrap();
+        assert_eq!(profile.fsync_append_size(), 512);
+    }
+
+    #[test]
+    fn rejects_blocks_larger_than_their_file() {
+        assert_eq!(
+            error("[disk_qd]\nfile_size_mb = 1\nblock_size_kb = 4096"),
+            "'disk_qd.block_size_kb' of 4096 is larger than 'disk_qd.file_size_mb' of 1"
+        );
+        assert_eq!(
+            error("[disk_sweep]\nfile_size_mb = 8"),
+            "'disk_sweep.block_sizes_kb' of 16384 is larger than 'disk_sweep.file_size_mb' of 8"
+        );
+        assert!(standard_with("[disk_sweep]\nfile_size_mb = 16").is_ok(), "a block the size of the file fits");
+    }
+
+    #[test]
+    fn rejects_sizes_that_overflow() {
+        let huge = usize::MAX / 2;
+        assert_eq!(error(&format!("[disk]\nsize_mb = {}", huge)), format!("'disk.size_mb' of {} is too large", huge));
+    }
+
+    #[test]
+    fn rejects_unknown_tests_and_weights() {
+        assert!(error("tests = [\"gpu\"]").starts_with("unknown test 'gpu'"));
+        assert_eq!(error("[score.weights]\ngpu_score = 1.0"), "unknown metric 'gpu_score' in 'score.weights'");
+        assert_eq!(error("[score.weights]\ncpu_score = -1.0"), "'score.weights.cpu_score' must be a number of at least 0");
+    }
+}
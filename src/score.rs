@@ -0,0 +1,193 @@
+// Composite score: every metric normalized against the reference machine, where the
+// reference scores 1000, and combined with a weighted geometric mean
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::BenchmarkResults;
+
+/// What a metric scores when it equals the reference value
+pub const REFERENCE_SCORE: f64 = 1000.0;
+
+/// A metric's value on the reference machine
+pub struct Reference {
+    pub key: &'static str,
+    pub value: f64,
+    /// Default weight in the composite; metrics with weight 0 are scored but not combined
+    pub weight: f64,
+}
+
+const fn reference(key: &'static str, value: f64, weight: f64) -> Reference {
+    Reference { key, value, weight }
+}
+
+/// The reference machine is nominal rather than a measured computer; the values are in the
+/// units of `BenchmarkResults`. Memory read/write and sequential disk speed are the
+/// thresholds of the former "Good" ratings. The rest are round estimates for a four-core
+/// desktop with dual-channel DDR4 and a SATA SSD: all-core CPU four times single-core in
+/// this tool's own workload, about 100 ns DRAM latency, STREAM in the 20 GB/s range and
+/// 10,000 IOPS for 4 KB random I/O. Changing a value changes every score, so scores from
+/// different tool versions are only comparable while the values stay the same.
+pub const REFERENCE_MACHINE: [Reference; 30] = [
+    reference("cpu_score", 20_000.0, 1.0),
+    reference("cpu_multi_score", 80_000.0, 1.0),
+    reference("memory_read_speed", 5_000.0, 0.5),
+    reference("memory_write_speed", 5_000.0, 0.5),
+    reference("memory_latency", 100.0, 0.5),
+    reference("stream_copy", 20.0, 0.0),
+    reference("stream_scale", 20.0, 0.0),
+    reference("stream_add", 22.0, 0.0),
+    reference("stream_triad", 22.0, 0.5),
+    reference("disk_read_speed", 250.0, 0.5),
+    reference("disk_write_speed", 250.0, 0.5),
+    reference("disk_random_read_iops", 10_000.0, 0.5),
+    reference("disk_random_write_iops", 10_000.0, 0.5),
+    reference("disk_mixed_iops", 10_000.0, 0.0),
+    reference("disk_read_p99", 500.0, 0.0),
+    reference("disk_write_p99", 1_000.0, 0.0),
+    reference("disk_direct_read_speed", 250.0, 0.0),
+    reference("disk_direct_write_speed", 250.0, 0.0),
+    reference("disk_fsync_writes_per_sec", 500.0, 0.0),
+    reference("disk_fsync_p99", 5_000.0, 0.0),
+    reference("disk_fdatasync_writes_per_sec", 500.0, 0.0),
+    reference("disk_fdatasync_p99", 5_000.0, 0.0),
+    reference("disk_meta_file_create", 20_000.0, 0.0),
+    reference("disk_meta_file_stat", 200_000.0, 0.0),
+    reference("disk_meta_file_rename", 50_000.0, 0.0),
+    reference("disk_meta_file_delete", 30_000.0, 0.0),
+    reference("disk_meta_dir_create", 20_000.0, 0.0),
+    reference("disk_meta_dir_stat", 200_000.0, 0.0),
+    reference("disk_meta_dir_rename", 50_000.0, 0.0),
+    reference("disk_meta_dir_delete", 20_000.0, 0.0),
+];
+
+/// Parts of the system a category score covers
+pub const CATEGORIES: [&str; 3] = ["cpu", "memory", "disk"];
+
+/// The category of the benchmark that produces a metric
+fn category(benchmark: &str) -> &'static str {
+    match benchmark {
+        "cpu" => "cpu",
+        "memory" | "cache" => "memory",
+        _ => "disk",
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MetricScore {
+    pub key: String,
+    pub category: String,
+    pub value: f64,
+    pub reference: f64,
+    pub weight: f64,
+    pub score: f64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CompositeScore {
+    /// Weighted geometric mean of every weighted metric that was measured
+    pub overall: f64,
+    /// The same mean over the metrics of each category that has any
+    pub categories: BTreeMap<String, f64>,
+    /// Every measured metric with a reference value, weighted or not
+    pub metrics: Vec<MetricScore>,
+}
+
+/// Weight of each metric: the reference machine's defaults with `overrides` on top
+pub fn weights(overrides: &BTreeMap<String, f64>) -> BTreeMap<&'static str, f64> {
+    REFERENCE_MACHINE
+        .iter()
+        .map(|r| (r.key, overrides.get(r.key).copied().unwrap_or(r.weight)))
+        .collect()
+}
+
+fn weighted_geometric_mean<'a>(scores: impl Iterator<Item = &'a MetricScore>) -> Option<f64> {
+    let (log_sum, weight_sum) = scores
+        .filter(|m| m.weight > 0.0)
+        .fold((0.0, 0.0), |(log_sum, weight_sum), m| (log_sum + m.weight * m.score.ln(), weight_sum + m.weight));
+    (weight_sum > 0.0).then(|| (log_sum / weight_sum).exp())
+}
+
+impl CompositeScore {
+    /// Scores the metrics of `benchmarks` that were measured; None when none of them has
+    /// any weight
+    pub fn compute(results: &BenchmarkResults, benchmarks: &[&str], overrides: &BTreeMap<String, f64>) -> Option<Self> {
+        let weights = weights(overrides);
+        let metrics: Vec<MetricScore> = results
+            .metrics()
+            .iter()
+            .filter(|m| benchmarks.contains(&m.benchmark) && m.value > 0.0 && m.value.is_finite())
+            .filter_map(|m| {
+                let reference = REFERENCE_MACHINE.iter().find(|r| r.key == m.key)?;
+                let ratio = if m.higher_is_better { m.value / reference.value } else { reference.value / m.value };
+                Some(MetricScore {
+                    key: m.key.to_string(),
+                    category: category(m.benchmark).to_string(),
+                    value: m.value,
+                    reference: reference.value,
+                    weight: weights[m.key],
+                    score: REFERENCE_SCORE * ratio,
+                })
+            })
+            .collect();
+
+        let overall = weighted_geometric_mean(metrics.iter())?;
+        let categories = CATEGORIES
+            .iter()
+            .filter_map(|&c| weighted_geometric_mean(metrics.iter().filter(|m| m.category == c)).map(|s| (c.to_string(), s)))
+            .collect();
+        Some(Self { overall, categories, metrics })
+    }
+
+    pub fn category(&self, category: &str) -> Option<f64> {
+        self.categories.get(category).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_close;
+
+    #[test]
+    fn the_reference_value_scores_the_reference_score() {
+        let score = CompositeScore::compute(&BenchmarkResults::from_metrics(&[("cpu_score", 20_000.0)]), &["cpu"], &BTreeMap::new()).unwrap();
+        assert_close(score.overall, REFERENCE_SCORE);
+        assert_close(score.category("cpu").unwrap(), REFERENCE_SCORE);
+        assert_eq!(score.category("disk"), None);
+    }
+
+    #[test]
+    fn lower_is_better_inverts_the_ratio() {
+        // Half the reference latency is twice as good
+        let score = CompositeScore::compute(&BenchmarkResults::from_metrics(&[("memory_latency", 50.0)]), &["memory"], &BTreeMap::new()).unwrap();
+        assert_close(score.metrics[0].score, 2000.0);
+        assert_close(score.overall, 2000.0);
+    }
+
+    #[test]
+    fn zero_weights_are_scored_but_not_combined() {
+        // Mixed IOPS scores 10000 with a default weight of 0
+        let measured = BenchmarkResults::from_metrics(&[("disk_read_speed", 250.0), ("disk_mixed_iops", 100_000.0)]);
+        let score = CompositeScore::compute(&measured, &["disk"], &BTreeMap::new()).unwrap();
+        assert_eq!(score.metrics.len(), 2);
+        assert_close(score.overall, 1000.0);
+
+        // Weighted in by an override, it counts twice as much as the sequential read
+        let overrides = BTreeMap::from([("disk_mixed_iops".to_string(), 1.0)]);
+        let score = CompositeScore::compute(&measured, &["disk"], &overrides).unwrap();
+        assert_close(score.overall, ((0.5 * 1000f64.ln() + 10_000f64.ln()) / 1.5).exp());
+
+        // Weighted out, nothing is left to combine
+        let overrides = BTreeMap::from([("disk_read_speed".to_string(), 0.0)]);
+        assert!(CompositeScore::compute(&measured, &["disk"], &overrides).is_none());
+    }
+
+    #[test]
+    fn benchmarks_that_did_not_run_have_no_score() {
+        let measured = BenchmarkResults::from_metrics(&[("cpu_score", 20_000.0)]);
+        assert!(CompositeScore::compute(&measured, &["memory"], &BTreeMap::new()).is_none());
+        assert!(CompositeScore::compute(&BenchmarkResults::default(), &["cpu"], &BTreeMap::new()).is_none());
+    }
+}
@@ -0,0 +1,193 @@
+// Statistical summaries over repeated benchmark iterations
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::BenchmarkResults;
+
+/// Two-sided 95% Student's t critical values for 1..=30 degrees of freedom
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Critical values past the table by degrees of freedom, ending with the normal value
+const T_95_TAIL: [(f64, f64); 5] = [(30.0, 2.042), (40.0, 2.021), (60.0, 2.000), (120.0, 1.980), (f64::INFINITY, 1.960)];
+
+/// Two-sided 95% critical value for `df` ≥ 1 degrees of freedom; past the table it is
+/// interpolated linearly in 1/df, which follows the true curve to within 0.001
+fn t_95(df: usize) -> f64 {
+    if let Some(&t) = T_95.get(df - 1) {
+        return t;
+    }
+    let x = 1.0 / df as f64;
+    T_95_TAIL
+        .windows(2)
+        .find(|pair| df as f64 <= pair[1].0)
+        .map_or(1.960, |pair| {
+            let ((df_low, t_low), (df_high, t_high)) = (pair[0], pair[1]);
+            let (x_low, x_high) = (1.0 / df_low, 1.0 / df_high);
+            t_high + (t_low - t_high) * (x - x_high) / (x_low - x_high)
+        })
+}
+
+/// Outlier rejection needs enough samples for the quartiles to mean something
+const MIN_SAMPLES_FOR_OUTLIERS: usize = 4;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Summary {
+    /// Samples kept after outlier rejection
+    pub samples: usize,
+    pub outliers_rejected: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+    pub std_dev: f64,
+    /// Coefficient of variation in percent
+    pub cv_pct: f64,
+    pub ci95_low: f64,
+    pub ci95_high: f64,
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = p * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+}
+
+/// Summarizes `values` after dropping points outside the Tukey fences (1.5 × IQR)
+pub fn summarize(values: &[f64]) -> Summary {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    if sorted.len() >= MIN_SAMPLES_FOR_OUTLIERS {
+        let (q1, q3) = (percentile(&sorted, 0.25), percentile(&sorted, 0.75));
+        let iqr = q3 - q1;
+        sorted.retain(|&v| v >= q1 - 1.5 * iqr && v <= q3 + 1.5 * iqr);
+    }
+
+    let n = sorted.len();
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+    let std_dev = if n > 1 {
+        (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    let t = if n > 1 { t_95(n - 1) } else { 0.0 };
+    let margin = t * std_dev / (n as f64).sqrt();
+
+    Summary {
+        samples: n,
+        outliers_rejected: values.len() - n,
+        mean,
+        median: percentile(&sorted, 0.5),
+        min: sorted[0],
+        max: sorted[n - 1],
+        std_dev,
+        cv_pct: if mean != 0.0 { std_dev / mean * 100.0 } else { 0.0 },
+        ci95_low: mean - margin,
+        ci95_high: mean + margin,
+    }
+}
+
+/// Summarizes every metric of the benchmarks that ran. The returned results hold the
+/// outlier-filtered mean of each metric; detail tables are those of the last run.
+pub fn aggregate(runs: &[BenchmarkResults], benchmarks: &[&str]) -> (BenchmarkResults, BTreeMap<String, Summary>) {
+    let mut results = runs[runs.len() - 1].clone();
+    let mut summaries = BTreeMap::new();
+
+    let per_run: Vec<_> = runs.iter().map(|r| r.metrics()).collect();
+    for (i, metric) in per_run[0].iter().enumerate() {
+        if !benchmarks.contains(&metric.benchmark) {
+            continue;
+        }
+        let values: Vec<f64> = per_run.iter().map(|m| m[i].value).collect();
+        let summary = summarize(&values);
+        results.set_metric(metric.key, summary.mean);
+        summaries.insert(metric.key.to_string(), summary);
+    }
+    (results, summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_close;
+
+    #[test]
+    fn a_single_value_has_no_spread() {
+        let s = summarize(&[5.0]);
+        assert_eq!((s.samples, s.outliers_rejected), (1, 0));
+        assert_close(s.mean, 5.0);
+        assert_close(s.median, 5.0);
+        assert_close(s.std_dev, 0.0);
+        assert_close(s.ci95_low, 5.0);
+        assert_close(s.ci95_high, 5.0);
+    }
+
+    #[test]
+    fn few_samples_keep_their_outliers() {
+        let s = summarize(&[1.0, 2.0, 100.0]);
+        assert_eq!((s.samples, s.outliers_rejected), (3, 0));
+        assert_close(s.max, 100.0);
+    }
+
+    #[test]
+    fn values_beyond_the_tukey_fences_are_rejected() {
+        // Q1 = 11 and Q3 = 13, so the fences are 8 and 16
+        let s = summarize(&[10.0, 11.0, 12.0, 13.0, 100.0]);
+        assert_eq!((s.samples, s.outliers_rejected), (4, 1));
+        assert_close(s.max, 13.0);
+
+        let s = summarize(&[10.0, 11.0, 12.0, 13.0, 16.0]);
+        assert_eq!(s.outliers_rejected, 0, "a value on the fence is kept");
+    }
+
+    #[test]
+    fn the_interval_uses_t_for_n_minus_one_degrees_of_freedom() {
+        // n = 2: one degree of freedom, std dev √2
+        let s = summarize(&[1.0, 3.0]);
+        assert_close(s.ci95_high - s.mean, 12.706);
+
+        // n = 3: two degrees of freedom, std dev 1
+        let s = summarize(&[1.0, 2.0, 3.0]);
+        assert_close(s.ci95_high - s.mean, 4.303 / 3f64.sqrt());
+
+        // n = 40: 39 degrees of freedom, past the table
+        let values: Vec<f64> = (0..40).map(|i| (i % 2) as f64).collect();
+        let s = summarize(&values);
+        assert_close(s.ci95_high - s.mean, t_95(39) * s.std_dev / 40f64.sqrt());
+    }
+
+    #[test]
+    fn t_values_past_the_table_follow_the_distribution() {
+        assert_close(t_95(30), 2.042);
+        assert_close(t_95(40), 2.021);
+        assert_close(t_95(120), 1.980);
+        // True values: 2.0227 at 39 and 1.9799 at 122 degrees of freedom
+        assert!((t_95(39) - 2.0227).abs() < 0.001);
+        assert!((t_95(122) - 1.9799).abs() < 0.001);
+        assert!((t_95(100_000) - 1.960).abs() < 0.001);
+        assert!(t_95(31) < t_95(30) && t_95(31) > t_95(40));
+    }
+
+    #[test]
+    fn aggregate_averages_only_the_benchmarks_that_ran() {
+        let runs: Vec<BenchmarkResults> = [(100.0, 90.0), (200.0, 80.0), (300.0, 70.0)]
+            .iter()
+            .map(|&(cpu, latency)| BenchmarkResults::from_metrics(&[("cpu_score", cpu), ("memory_latency", latency)]))
+            .collect();
+
+        let (results, summaries) = aggregate(&runs, &["cpu"]);
+        assert!(summaries.keys().all(|key| key.starts_with("cpu_")));
+        assert_eq!(summaries["cpu_score"].samples, 3);
+        assert_close(results.cpu_score, 200.0);
+        assert_close(results.memory_latency, 70.0);
+    }
+}
@@ -0,0 +1,15 @@
+// Thread scaling table of the CPU benchmark
+
+use colored::*;
+use eonflux_benchmark::cpu::CpuScalingPoint;
+
+pub fn print_scaling_table(points: &[CpuScalingPoint]) {
+    println!("{}", "Thread Scaling:".bright_yellow());
+    println!("{:>8} {:>16} {:>9} {:>11}", "Threads", "Ops/second", "Speedup", "Efficiency");
+    for p in points {
+        println!(
+            "{:>8} {:>16.2} {:>8.2}x {:>10.1}%",
+            p.threads, p.ops_per_sec, p.speedup, p.efficiency_pct
+        );
+    }
+}
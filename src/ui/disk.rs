@@ -0,0 +1,144 @@
+// Tables and charts of the disk benchmarks
+
+use colored::*;
+use eonflux_benchmark::disk::{BlockSizePoint, DurableWriteResult, MetadataResult, QueueDepthPoint, RandomTestResult, Throughput};
+use eonflux_benchmark::latency::LatencySummary;
+use eonflux_benchmark::memory::format_size;
+
+use super::latency;
+
+const CHART_WIDTH: usize = 40;
+
+pub fn print_block_size_table(points: &[BlockSizePoint]) {
+    println!("{}", "Throughput and IOPS by Block Size:".bright_yellow());
+    println!(
+        "{:>8} {:>22} {:>22} {:>22} {:>22}",
+        "Block", "Sequential Read", "Sequential Write", "Random Read", "Random Write"
+    );
+    let units = format!("{:>12} {:>9}", "MB/s", "IOPS");
+    println!("{:>8} {} {} {} {}", "", units, units, units, units);
+    for p in points {
+        let cell = |t: &Throughput| format!("{:>12.1} {:>9.0}", t.mb_per_sec, t.iops);
+        println!(
+            "{:>8} {:>22} {:>22} {:>22} {:>22}",
+            format_size(p.block_size),
+            cell(&p.sequential_read),
+            cell(&p.sequential_write),
+            cell(&p.random_read),
+            cell(&p.random_write)
+        );
+    }
+}
+
+/// One bar per block size for reads and one for writes, scaled to the largest value;
+/// `log` scales the bars logarithmically, which suits IOPS spanning several magnitudes
+fn print_chart(title: &str, unit: &str, rows: &[(usize, f64, f64)], log: bool) {
+    let max = rows.iter().map(|&(_, r, w)| r.max(w)).fold(0.0, f64::max);
+    if max <= 0.0 {
+        return;
+    }
+    let scale = |v: f64| {
+        let fraction = if log { (v.max(1.0)).ln() / max.max(1.0).ln().max(f64::EPSILON) } else { v / max };
+        ((fraction * CHART_WIDTH as f64) as usize).max(1)
+    };
+
+    println!("{} ({} {}, {} {})", title.bright_yellow(), "█ read".bright_cyan(), unit, "█ write".bright_magenta(), unit);
+    for &(block_size, read, write) in rows {
+        let size = format_size(block_size);
+        println!("{:>8} {} {:.0}", size, "█".repeat(scale(read)).bright_cyan(), read);
+        println!("{:>8} {} {:.0}", "", "█".repeat(scale(write)).bright_magenta(), write);
+    }
+}
+
+/// Sequential throughput, which matters for large blocks, and random IOPS, which matters
+/// for small ones
+pub fn print_block_size_charts(points: &[BlockSizePoint]) {
+    let sequential: Vec<_> = points
+        .iter()
+        .map(|p| (p.block_size, p.sequential_read.mb_per_sec, p.sequential_write.mb_per_sec))
+        .collect();
+    print_chart("Sequential Throughput", "MB/s", &sequential, false);
+    println!();
+    let random: Vec<_> = points
+        .iter()
+        .map(|p| (p.block_size, p.random_read.iops, p.random_write.iops))
+        .collect();
+    print_chart("Random IOPS, log scale", "IOPS", &random, true);
+}
+
+pub fn print_queue_depth_table(points: &[QueueDepthPoint], block_size: usize, io_uring: bool) {
+    println!(
+        "{} ({} random I/O, {} engine)",
+        "Queue Depth Scaling".bright_yellow(),
+        format_size(block_size),
+        if io_uring { "io_uring" } else { "threaded" }
+    );
+    println!(
+        "{:>5} {:>8} {:>12} {:>12} {:>12} {:>12}",
+        "QD", "Workers", "Read IOPS", "Read MB/s", "Write IOPS", "Write MB/s"
+    );
+    for p in points {
+        println!(
+            "{:>5} {:>8} {:>12.0} {:>12.1} {:>12.0} {:>12.1}",
+            p.queue_depth, p.workers, p.random_read.iops, p.random_read.mb_per_sec, p.random_write.iops, p.random_write.mb_per_sec
+        );
+    }
+}
+
+pub fn print_random_tests(tests: &[RandomTestResult]) {
+    let Some(first) = tests.first() else {
+        return;
+    };
+    println!("{}", format!("Random I/O ({} blocks):", format_size(first.block_size)).bright_yellow());
+    println!(
+        "{:<14} {:>10} {:>10} {:>14} {:>14}",
+        "Test", "IOPS", "MB/s", "Read p99", "Write p99"
+    );
+    for t in tests {
+        let p99 = |s: &Option<LatencySummary>| s.as_ref().map_or("-".to_string(), |s| format!("{:.1} µs", s.p99_us));
+        println!(
+            "{:<14} {:>10.0} {:>10.1} {:>14} {:>14}",
+            t.name,
+            t.throughput.iops,
+            t.throughput.mb_per_sec,
+            p99(&t.read_latency),
+            p99(&t.write_latency)
+        );
+    }
+}
+
+pub fn print_durable_writes(results: &[DurableWriteResult]) {
+    let Some(first) = results.first() else {
+        return;
+    };
+    println!("{}", format!("Durable Writes ({} appends):", format_size(first.append_size)).bright_yellow());
+    println!("{:<12} {:>10} {:>12}", "Method", "Writes", "Writes/s");
+    for r in results {
+        println!("{:<12} {:>10} {:>12.0}", r.method, r.writes, r.writes_per_sec);
+    }
+
+    let rows: Vec<(&str, &LatencySummary)> = results
+        .iter()
+        .filter_map(|r| r.sync_latency.as_ref().map(|s| (r.method.as_str(), s)))
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+    println!("\n{}", "Sync Latency:".bright_yellow());
+    latency::print_table(&rows);
+    for (label, summary) in rows {
+        println!();
+        latency::print_histogram(label, summary);
+    }
+}
+
+pub fn print_metadata_table(results: &[MetadataResult]) {
+    let Some(first) = results.first() else {
+        return;
+    };
+    println!("{}", format!("Metadata Operations ({} per type):", first.count).bright_yellow());
+    println!("{:<14} {:>12} {:>12}", "Operation", "Ops/s", "µs/op");
+    for r in results {
+        println!("{:<14} {:>12.0} {:>12.1}", r.operation, r.ops_per_sec, 1e6 / r.ops_per_sec);
+    }
+}
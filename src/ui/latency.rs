@@ -0,0 +1,60 @@
+// Latency percentile tables and log-scale histograms
+
+use colored::*;
+use eonflux_benchmark::latency::LatencySummary;
+
+const HISTOGRAM_WIDTH: usize = 40;
+
+fn format_us(us: f64) -> String {
+    match us {
+        us if us >= 1_000_000.0 => format!("{:.2} s", us / 1_000_000.0),
+        us if us >= 1_000.0 => format!("{:.2} ms", us / 1_000.0),
+        us => format!("{:.1} µs", us),
+    }
+}
+
+/// One row per operation type with its percentiles
+pub fn print_table(rows: &[(&str, &LatencySummary)]) {
+    println!(
+        "{:<18} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Operation", "Samples", "Min", "Avg", "p50", "p90", "p99", "p99.9", "Max"
+    );
+    for (label, s) in rows {
+        println!(
+            "{:<18} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            label,
+            s.samples,
+            format_us(s.min_us),
+            format_us(s.avg_us),
+            format_us(s.p50_us),
+            format_us(s.p90_us),
+            format_us(s.p99_us),
+            format_us(s.p999_us),
+            format_us(s.max_us)
+        );
+    }
+}
+
+/// Histogram bounds are round numbers, so they need no decimals
+fn format_bound(us: f64) -> String {
+    match us {
+        us if us >= 1_000_000.0 => format!("{} s", us / 1_000_000.0),
+        us if us >= 1_000.0 => format!("{} ms", us / 1_000.0),
+        us => format!("{} µs", us),
+    }
+}
+
+pub fn print_histogram(label: &str, summary: &LatencySummary) {
+    println!("{}", format!("{} Latency Histogram:", label).bright_yellow());
+    let largest = summary.histogram.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+    for bucket in &summary.histogram {
+        let width = (bucket.count * HISTOGRAM_WIDTH).div_ceil(largest);
+        println!(
+            "{:>11} {:<width$} {:>5.1}%",
+            format!("≤ {}", format_bound(bucket.upper_us)),
+            "█".repeat(width).bright_cyan(),
+            bucket.count as f64 / summary.samples as f64 * 100.0,
+            width = HISTOGRAM_WIDTH
+        );
+    }
+}
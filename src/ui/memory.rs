@@ -0,0 +1,51 @@
+// Cache sweep chart and STREAM bandwidth table of the memory and cache benchmarks
+
+use colored::*;
+use eonflux_benchmark::memory::{format_size, CacheLevel, CacheSweepPoint, StreamResult};
+
+const CHART_WIDTH: usize = 40;
+
+pub fn print_sweep(points: &[CacheSweepPoint], levels: &[CacheLevel]) {
+    if points.is_empty() {
+        return;
+    }
+    let min = points.iter().map(|p| p.latency_ns).fold(f64::INFINITY, f64::min).max(0.01);
+    let max = points.iter().map(|p| p.latency_ns).fold(0.0, f64::max);
+    let span = (max / min).ln().max(f64::EPSILON);
+
+    println!("{}", "Latency by Working-Set Size (log scale):".bright_yellow());
+    println!("{:>10} {:>11} {:>11}  Latency", "Size", "Latency", "Read BW");
+    for p in points {
+        let width = 1 + ((p.latency_ns.max(min) / min).ln() / span * (CHART_WIDTH - 1) as f64) as usize;
+        println!(
+            "{:>10} {:>8.2} ns {:>6.2} GB/s  {}",
+            format_size(p.size_bytes),
+            p.latency_ns,
+            p.bandwidth_gbs,
+            "█".repeat(width).bright_cyan()
+        );
+    }
+
+    println!();
+    println!("{}", "Detected Levels:".bright_yellow());
+    for (i, level) in levels.iter().enumerate() {
+        let extent = if i + 1 < levels.len() {
+            format!("up to ~{}", format_size(level.max_size_bytes))
+        } else {
+            "beyond that".to_string()
+        };
+        println!("► {:<6} {:<16} {:>8.2} ns", level.name, extent, level.latency_ns);
+    }
+}
+
+pub fn print_stream_table(results: &[StreamResult]) {
+    let threads = results.first().map_or(1, |r| r.threads);
+    println!("{}", "STREAM Bandwidth:".bright_yellow());
+    println!("{:<8} {:>14} {:>18}", "Kernel", "1 thread", format!("All CPUs ({})", threads));
+    for r in results {
+        println!(
+            "{:<8} {:>9.2} GB/s {:>13.2} GB/s",
+            r.kernel, r.single_thread_gbs, r.multi_thread_gbs
+        );
+    }
+}
@@ -0,0 +1,9 @@
+// Terminal output of the binary: the tables and charts of each benchmark's results and
+// the terminal state the menu changes, kept out of the library so it never prints
+
+pub mod cpu;
+pub mod disk;
+pub mod latency;
+pub mod memory;
+pub mod stats;
+pub mod terminal;
@@ -0,0 +1,32 @@
+// Statistical summary table over repeated iterations
+
+use std::collections::BTreeMap;
+
+use colored::*;
+use eonflux_benchmark::stats::Summary;
+use eonflux_benchmark::BenchmarkResults;
+
+pub fn print_summaries(results: &BenchmarkResults, summaries: &BTreeMap<String, Summary>) {
+    println!("{}", "Statistical Summary:".bright_yellow());
+    println!(
+        "{:<16} {:>12} {:>12} {:>12} {:>12} {:>10} {:>7}  {:<27} Outliers",
+        "Metric", "Mean", "Median", "Min", "Max", "Std Dev", "CV", "95% CI"
+    );
+    for metric in results.metrics() {
+        let Some(s) = summaries.get(metric.key) else {
+            continue;
+        };
+        println!(
+            "{:<16} {:>12.2} {:>12.2} {:>12.2} {:>12.2} {:>10.2} {:>6.1}%  {:<27} {}",
+            metric.label,
+            s.mean,
+            s.median,
+            s.min,
+            s.max,
+            s.std_dev,
+            s.cv_pct,
+            format!("[{:.2}, {:.2}]", s.ci95_low, s.ci95_high),
+            s.outliers_rejected
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// Restoration of the terminal however the process ends: normally, through an error or
+// panic, or on Ctrl-C
+
+use std::io::{self, stdout};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use colored::*;
+use crossterm::{cursor, execute, terminal};
+use eonflux_benchmark::cleanup;
+
+/// Whether a `RawTerminal` is active, for the Ctrl-C handler to restore
+static RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Raw terminal mode for the interactive menu, left again when the guard is dropped
+pub struct RawTerminal;
+
+impl RawTerminal {
+    pub fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        RAW_MODE.store(true, Ordering::SeqCst);
+        Ok(Self)
+    }
+}
+
+fn restore_terminal() {
+    if RAW_MODE.swap(false, Ordering::SeqCst) {
+        let _ = execute!(stdout(), terminal::EnableLineWrap, cursor::Show);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// On Ctrl-C or a termination request, removes the test files of the running benchmark,
+/// restores the terminal and exits with `exit_code`
+pub fn install_interrupt_handler(exit_code: i32) {
+    let result = ctrlc::set_handler(move || {
+        cleanup::remove_temp_paths();
+        restore_terminal();
+        eprintln!("\n{} benchmark interrupted", "error:".bright_red());
+        std::process::exit(exit_code);
+    });
+    if let Err(e) = result {
+        eprintln!("{} cannot handle Ctrl-C, test files may be left behind: {}", "warning:".bright_yellow(), e);
+    }
+}